@@ -4,14 +4,33 @@ pub const SEED_CONFIG_ACCOUNT: &[u8] = b"config";
 pub const SEED_COLLATERAL_ACCOUNT: &[u8] = b"collateral";
 pub const SEED_SOL_ACCOUNT: &[u8] = b"sol";
 pub const SEED_MINT_ACCOUNT: &[u8] = b"mint";
+pub const SEED_PSM_VAULT_ACCOUNT: &[u8] = b"psm_vault";
+pub const SEED_SETTLEMENT_ACCOUNT: &[u8] = b"settlement";
+pub const SEED_BLOCKLIST_ACCOUNT: &[u8] = b"blocklist";
+pub const SEED_SAVINGS_VAULT_ACCOUNT: &[u8] = b"savings_vault";
+pub const SEED_SAVINGS_ACCOUNT: &[u8] = b"savings";
+pub const SEED_AUCTION_ACCOUNT: &[u8] = b"auction";
 
 #[constant]
 pub const FEED_ID: &str = "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
 pub const MAXIMUM_AGE: u64 = 100; // allow pricefeed 100 sec old, to avoid stale price feed errors
-pub const PRICE_FEED_DECIMAL_ADJUSTMENT: u128 = 10; // price feed returns 1e8, multiple by 10 to match lamports 10e9
+pub const MAX_CONFIDENCE_BPS: u64 = 200; // reject price if confidence interval is more than 2% of the price
+pub const MAX_PRICE_DEVIATION_BPS: u64 = 100; // reject price if secondary feed deviates more than 1%
 
 // Constants for configuration values
 pub const LIQUIDATION_THRESHOLD: u64 = 50; // 200% over-collateralized
 pub const LIQUIDATION_BONUS: u64 = 10; // 10% bonus lamports when liquidating
-pub const MIN_HEALTH_FACTOR: u64 = 1;
+pub const MAX_CLOSE_FACTOR: u64 = 50; // liquidator can burn at most 50% of the minted amount per liquidation
+pub const HEALTH_FACTOR_PRECISION: u64 = 10_000; // health factor in basis points, 10_000 = 1.0
+pub const MIN_HEALTH_FACTOR: u64 = 10_000; // 1.0
 pub const MINT_DECIMALS: u8 = 9;
+pub const BASIS_POINTS: u64 = 10_000;
+pub const MAX_BLOCKLIST_LEN: usize = 100; // max number of addresses on the blocklist
+pub const MAX_RATE_BPS: u64 = 10_000; // stability fee and savings rate are capped at 100% per year
+pub const SECONDS_PER_YEAR: u64 = 31_536_000; // 365 days, used to pro-rate annual rates
+pub const STABILITY_FEE_INDEX_PRECISION: u64 = 1_000_000_000_000_000_000; // stability fee index of 1.0
+pub const SAVINGS_VIRTUAL_SHARES: u64 = 1_000_000; // virtual shares and assets priced into the savings vault, 10^6 shares per unit
+pub const SAVINGS_VIRTUAL_ASSETS: u64 = 1; // so a donation to an empty vault cannot inflate the share price
+pub const AUCTION_START_PREMIUM_BPS: u64 = 2_000; // auctions start 20% above the oracle price
+pub const AUCTION_FLOOR_BPS: u64 = 8_000; // auctions never go below 80% of the oracle price at the start
+pub const AUCTION_DURATION: i64 = 3_600; // auction price decays linearly to the floor over 1 hour, then it expires
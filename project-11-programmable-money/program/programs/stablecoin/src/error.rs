@@ -8,4 +8,62 @@ pub enum CustomError {
     AboveMinimumHealthFactor,
     #[msg("Price should not be negative")]
     InvalidPrice,
+    #[msg("Liquidation Amount Must Be Greater Than Zero")]
+    InvalidLiquidationAmount,
+    #[msg("Liquidation Amount Exceeds Maximum Close Factor")]
+    AboveMaximumCloseFactor,
+    #[msg("Liquidation Must Improve Health Factor")]
+    HealthFactorNotImproved,
+    #[msg("Liquidated Lamports Below Minimum Amount Out")]
+    SlippageExceeded,
+    #[msg("Price Confidence Interval Too Wide")]
+    PriceConfidenceTooWide,
+    #[msg("Secondary Price Feed Account Required")]
+    MissingSecondaryPriceFeed,
+    #[msg("Price Deviation Between Feeds Too Large")]
+    PriceDeviationTooLarge,
+    #[msg("Fee Cannot Exceed 100%")]
+    InvalidFee,
+    #[msg("Swap Amount Must Be Greater Than Zero")]
+    InvalidSwapAmount,
+    #[msg("PSM Debt Ceiling Exceeded")]
+    PsmDebtCeilingExceeded,
+    #[msg("Insufficient PSM Reserves")]
+    InsufficientPsmReserves,
+    #[msg("Math Overflow")]
+    MathOverflow,
+    #[msg("Protocol Is Shut Down")]
+    ProtocolShutdown,
+    #[msg("Protocol Is Not Shut Down")]
+    ProtocolNotShutdown,
+    #[msg("Settlement Price Must Be Greater Than Zero")]
+    InvalidSettlementPrice,
+    #[msg("Settlement Amount Must Be Greater Than Zero")]
+    InvalidSettlementAmount,
+    #[msg("Cannot Close Position With Outstanding Debt")]
+    OutstandingDebt,
+    #[msg("Address Is Blocklisted")]
+    AddressBlocked,
+    #[msg("Blocklist Is Full")]
+    BlocklistFull,
+    #[msg("Rate Exceeds Maximum")]
+    InvalidRate,
+    #[msg("Savings Amount Must Be Greater Than Zero")]
+    InvalidSavingsAmount,
+    #[msg("Insufficient Savings Shares")]
+    InsufficientSavingsShares,
+    #[msg("Position Is In Auction")]
+    PositionInAuction,
+    #[msg("Position Is Not In Auction")]
+    PositionNotInAuction,
+    #[msg("Auction Is Still Active")]
+    AuctionActive,
+    #[msg("Auction Has Expired")]
+    AuctionExpired,
+    #[msg("Auction Amount Must Be Greater Than Zero")]
+    InvalidAuctionAmount,
+    #[msg("Oracle Bound Cannot Exceed 100%")]
+    InvalidOracleConfig,
+    #[msg("Not All Positions Are Settled")]
+    PositionsNotSettled,
 }
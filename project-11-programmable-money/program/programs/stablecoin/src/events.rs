@@ -0,0 +1,180 @@
+use crate::Config;
+use anchor_lang::prelude::*;
+
+// Emitted whenever the Config account is created or changed by an admin instruction
+#[event]
+pub struct ConfigUpdated {
+    pub authority: Pubkey,
+    pub liquidation_threshold: u64,
+    pub liquidation_bonus: u64,
+    pub max_close_factor: u64,
+    pub min_health_factor: u64,
+    pub max_confidence_bps: u64,
+    pub secondary_feed_id: Option<[u8; 32]>,
+    pub max_price_deviation_bps: u64,
+    pub psm_mint: Pubkey,
+    pub psm_fee_in_bps: u64,
+    pub psm_fee_out_bps: u64,
+    pub psm_debt_ceiling: u64,
+    pub stability_fee_bps: u64,
+    pub savings_rate_bps: u64,
+    pub is_shutdown: bool,
+    pub settlement_price: u64,
+}
+
+impl ConfigUpdated {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            authority: config.authority,
+            liquidation_threshold: config.liquidation_threshold,
+            liquidation_bonus: config.liquidation_bonus,
+            max_close_factor: config.max_close_factor,
+            min_health_factor: config.min_health_factor,
+            max_confidence_bps: config.max_confidence_bps,
+            secondary_feed_id: config.secondary_feed_id,
+            max_price_deviation_bps: config.max_price_deviation_bps,
+            psm_mint: config.psm_mint,
+            psm_fee_in_bps: config.psm_fee_in_bps,
+            psm_fee_out_bps: config.psm_fee_out_bps,
+            psm_debt_ceiling: config.psm_debt_ceiling,
+            stability_fee_bps: config.stability_fee_bps,
+            savings_rate_bps: config.savings_rate_bps,
+            is_shutdown: config.is_shutdown,
+            settlement_price: config.settlement_price,
+        }
+    }
+}
+
+#[event]
+pub struct BlocklistUpdated {
+    pub address: Pubkey,
+    pub blocked: bool,
+}
+
+#[event]
+pub struct CollateralDeposited {
+    pub depositor: Pubkey,
+    pub amount: u64,          // lamports deposited
+    pub lamport_balance: u64, // lamports held by the position after the deposit
+}
+
+#[event]
+pub struct Minted {
+    pub depositor: Pubkey,
+    pub amount: u64,        // stablecoins minted
+    pub amount_minted: u64, // total debt of the position after minting
+    pub health_factor: u64, // in basis points, 10_000 = 1.0
+}
+
+#[event]
+pub struct Burned {
+    pub depositor: Pubkey,  // owner of the position whose debt was repaid
+    pub burner: Pubkey,     // signer whose stablecoins were burned
+    pub amount: u64,        // stablecoins burned
+    pub amount_minted: u64, // total debt of the position after burning
+}
+
+#[event]
+pub struct Redeemed {
+    pub depositor: Pubkey,
+    pub amount: u64,          // lamports withdrawn
+    pub lamport_balance: u64, // lamports held by the position after the withdrawal
+    pub health_factor: u64,   // in basis points, 10_000 = 1.0
+}
+
+#[event]
+pub struct Liquidated {
+    pub liquidator: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_burned: u64,        // stablecoins burned by the liquidator
+    pub lamports_seized: u64,      // lamports paid to the liquidator, including the bonus
+    pub liquidation_bonus: u64,    // bonus lamports included in lamports_seized
+    pub health_factor_before: u64, // in basis points, 10_000 = 1.0
+    pub health_factor_after: u64,  // in basis points, 10_000 = 1.0
+}
+
+#[event]
+pub struct SwappedIn {
+    pub user: Pubkey,
+    pub amount_in: u64,     // reference stablecoins received by the PSM vault
+    pub amount_minted: u64, // stablecoins minted to the user, after the fee
+    pub fee: u64,           // stablecoins withheld as the swap in fee
+    pub psm_debt: u64,      // stablecoins outstanding through the PSM after the swap
+}
+
+#[event]
+pub struct SwappedOut {
+    pub user: Pubkey,
+    pub amount_burned: u64, // stablecoins burned by the user
+    pub amount_out: u64,    // reference stablecoins paid out of the PSM vault
+    pub fee: u64,           // stablecoins burned as the swap out fee
+    pub psm_debt: u64,      // stablecoins outstanding through the PSM after the swap
+}
+
+#[event]
+pub struct SavingsDeposited {
+    pub user: Pubkey,
+    pub amount: u64,        // stablecoins received by the savings vault
+    pub shares: u128,       // savings vault shares issued for the deposit
+    pub total_shares: u128, // savings vault shares outstanding after the deposit
+}
+
+#[event]
+pub struct SavingsWithdrawn {
+    pub user: Pubkey,
+    pub shares: u128,       // savings vault shares redeemed
+    pub amount: u64,        // stablecoins paid out of the savings vault, including interest
+    pub total_shares: u128, // savings vault shares outstanding after the withdrawal
+}
+
+#[event]
+pub struct PositionClosed {
+    pub depositor: Pubkey,
+    pub lamports_returned: u64,
+}
+
+#[event]
+pub struct PositionSettled {
+    pub depositor: Pubkey,
+    pub debt_settled: u64, // stablecoin debt cleared at the settlement price
+    pub lamports_settled: u64, // lamports moved into the settlement pool
+}
+
+#[event]
+pub struct Settled {
+    pub holder: Pubkey,
+    pub amount_burned: u64,
+    pub lamports_claimed: u64,
+}
+
+#[event]
+pub struct AuctionStarted {
+    pub depositor: Pubkey,
+    pub keeper: Pubkey,
+    pub debt: u64,        // stablecoin debt to be covered by the auction
+    pub lamports: u64,    // lamports available to bidders
+    pub start_price: u64, // SOL/USD price (9 decimals) the auction starts at
+}
+
+#[event]
+pub struct AuctionTaken {
+    pub taker: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_burned: u64,   // stablecoins burned by the taker
+    pub lamports_bought: u64, // lamports paid to the taker
+    pub price: u64,           // SOL/USD price (9 decimals) at the time of the take
+}
+
+#[event]
+pub struct AuctionEnded {
+    pub depositor: Pubkey,
+    pub lamports_returned: u64, // remaining collateral returned to the owner
+    pub remaining_debt: u64,    // debt left uncovered when the collateral ran out
+}
+
+#[event]
+pub struct AuctionExpired {
+    pub depositor: Pubkey,
+    pub keeper: Pubkey,
+    pub remaining_debt: u64, // debt left on the position, it can be auctioned again
+}
@@ -0,0 +1,63 @@
+use crate::{Config, SEED_CONFIG_ACCOUNT, SEED_MINT_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    freeze_account, thaw_account, FreezeAccount, Mint, ThawAccount, Token2022, TokenAccount,
+};
+
+// Shared by freeze_account and thaw_account
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority,
+        has_one = mint_account
+    )]
+    pub config_account: Account<'info, Config>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// Freeze a stablecoin token account using the mint PDA freeze authority, only the config authority can invoke
+pub fn process_freeze_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_MINT_ACCOUNT,
+        &[ctx.accounts.config_account.bump_mint_account],
+    ]];
+
+    freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
+            authority: ctx.accounts.mint_account.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+// Thaw a frozen stablecoin token account using the mint PDA freeze authority, only the config authority can invoke
+pub fn process_thaw_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_MINT_ACCOUNT,
+        &[ctx.accounts.config_account.bump_mint_account],
+    ]];
+
+    thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
+            authority: ctx.accounts.mint_account.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
@@ -1,8 +1,37 @@
+use crate::{
+    events::ConfigUpdated, Blocklist, Config, LIQUIDATION_BONUS, LIQUIDATION_THRESHOLD,
+    MAX_CLOSE_FACTOR, MAX_CONFIDENCE_BPS, MAX_PRICE_DEVIATION_BPS, MINT_DECIMALS,
+    MIN_HEALTH_FACTOR, SEED_BLOCKLIST_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_MINT_ACCOUNT,
+    STABILITY_FEE_INDEX_PRECISION,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{
-   Mint, Token2022,
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::{
+    token_2022::{
+        initialize_mint2,
+        spl_token_2022::{extension::ExtensionType, state::Mint as MintState},
+        InitializeMint2,
+    },
+    token_2022_extensions::transfer_fee::{transfer_fee_initialize, TransferFeeInitialize},
+    token_interface::{
+        metadata_pointer_initialize, permanent_delegate_initialize, token_metadata_initialize,
+        MetadataPointerInitialize, PermanentDelegateInitialize, Token2022, TokenMetadataInitialize,
+    },
 };
-use crate::{Config, LIQUIDATION_BONUS, LIQUIDATION_THRESHOLD, MINT_DECIMALS, MIN_HEALTH_FACTOR, SEED_CONFIG_ACCOUNT, SEED_MINT_ACCOUNT};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenMetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferFeeArgs {
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
 
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
@@ -10,8 +39,8 @@ pub struct InitializeConfig<'info> {
     pub authority: Signer<'info>,
 
     #[account(
-        init, 
-        payer = authority, 
+        init,
+        payer = authority,
         space = 8 + Config::INIT_SPACE,
         seeds = [SEED_CONFIG_ACCOUNT],
         bump,
@@ -20,29 +49,193 @@ pub struct InitializeConfig<'info> {
     #[account(
         init,
         payer = authority,
+        space = 8 + Blocklist::INIT_SPACE,
+        seeds = [SEED_BLOCKLIST_ACCOUNT],
+        bump,
+    )]
+    pub blocklist_account: Box<Account<'info, Blocklist>>,
+    /// CHECK: created and initialized as a Token-2022 mint with the selected extensions in the handler
+    #[account(
+        mut,
         seeds = [SEED_MINT_ACCOUNT],
         bump,
-        mint::decimals = MINT_DECIMALS,
-        mint::authority = mint_account,
-        mint::freeze_authority = mint_account,
-        mint::token_program = token_program
     )]
-    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub mint_account: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn process_initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+pub fn process_initialize_config(
+    ctx: Context<InitializeConfig>,
+    metadata: Option<TokenMetadataArgs>,
+    enable_permanent_delegate: bool,
+    transfer_fee: Option<TransferFeeArgs>,
+) -> Result<()> {
     *ctx.accounts.config_account = Config {
         authority: ctx.accounts.authority.key(),
         mint_account: ctx.accounts.mint_account.key(),
         liquidation_threshold: LIQUIDATION_THRESHOLD,
         liquidation_bonus: LIQUIDATION_BONUS,
+        max_close_factor: MAX_CLOSE_FACTOR,
         min_health_factor: MIN_HEALTH_FACTOR,
+        max_confidence_bps: MAX_CONFIDENCE_BPS,
+        secondary_feed_id: None,
+        max_price_deviation_bps: MAX_PRICE_DEVIATION_BPS,
+        psm_mint: Pubkey::default(),
+        psm_fee_in_bps: 0,
+        psm_fee_out_bps: 0,
+        psm_debt_ceiling: 0,
+        psm_debt: 0,
+        bump_psm_vault: 0,
+        is_shutdown: false,
+        settlement_price: 0,
+        bump_settlement_account: 0,
+        total_debt: 0,
+        settled_debt: 0,
+        stability_fee_bps: 0,
+        savings_rate_bps: 0,
+        accrued_stability_fees: 0,
+        stability_fee_index: STABILITY_FEE_INDEX_PRECISION as u128,
+        stability_fee_last_update: Clock::get()?.unix_timestamp,
+        savings_total_shares: 0,
+        savings_last_accrual: 0,
+        bump_savings_vault: 0,
         bump: ctx.bumps.config_account,
-        bump_mint_account:  ctx.bumps.mint_account,
+        bump_mint_account: ctx.bumps.mint_account,
     };
-    msg!("Initialized Config Acccount:{:#?}", ctx.accounts.config_account);
-    Ok(())
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+
+    ctx.accounts.blocklist_account.bump = ctx.bumps.blocklist_account;
+
+    create_mint_internal(&ctx, metadata, enable_permanent_delegate, transfer_fee)
 }
 
+// Create the stablecoin mint PDA with the optional Token-2022 extensions
+// - metadata pointer + token metadata stored on the mint itself, updatable by the config authority
+// - permanent delegate set to the config authority, for compliance seizures
+// - transfer fee, withheld fees can only be withdrawn by the mint PDA (see withdraw_transfer_fees)
+fn create_mint_internal(
+    ctx: &Context<InitializeConfig>,
+    metadata: Option<TokenMetadataArgs>,
+    enable_permanent_delegate: bool,
+    transfer_fee: Option<TransferFeeArgs>,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let mint_account = ctx.accounts.mint_account.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let signer_seeds: &[&[&[u8]]] = &[&[SEED_MINT_ACCOUNT, &[ctx.bumps.mint_account]]];
+
+    let mut extensions = vec![];
+    if metadata.is_some() {
+        extensions.push(ExtensionType::MetadataPointer);
+    }
+    if enable_permanent_delegate {
+        extensions.push(ExtensionType::PermanentDelegate);
+    }
+    if transfer_fee.is_some() {
+        extensions.push(ExtensionType::TransferFeeConfig);
+    }
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions)?;
+
+    // Token metadata is reallocated onto the mint after initialization, fund its final size up front
+    let metadata_space = match &metadata {
+        Some(metadata) => TokenMetadata {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+            ..Default::default()
+        }
+        .tlv_size_of()?,
+        None => 0,
+    };
+    let lamports = Rent::get()?.minimum_balance(space + metadata_space);
+
+    create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.authority.to_account_info(),
+                to: mint_account.clone(),
+            },
+            signer_seeds,
+        ),
+        lamports,
+        space as u64,
+        token_program.key,
+    )?;
+
+    // Extensions must be initialized before the mint itself
+    if metadata.is_some() {
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint_account.clone(),
+                },
+            ),
+            Some(authority),
+            Some(mint_account.key()),
+        )?;
+    }
+    if enable_permanent_delegate {
+        permanent_delegate_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                PermanentDelegateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint_account.clone(),
+                },
+            ),
+            &authority,
+        )?;
+    }
+    if let Some(transfer_fee) = &transfer_fee {
+        transfer_fee_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TransferFeeInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint_account.clone(),
+                },
+            ),
+            Some(&authority),
+            Some(&mint_account.key()),
+            transfer_fee.transfer_fee_basis_points,
+            transfer_fee.maximum_fee,
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(
+            token_program.clone(),
+            InitializeMint2 {
+                mint: mint_account.clone(),
+            },
+        ),
+        MINT_DECIMALS,
+        &mint_account.key(),
+        Some(&mint_account.key()),
+    )?;
+
+    if let Some(metadata) = metadata {
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    token_program_id: token_program.clone(),
+                    metadata: mint_account.clone(),
+                    update_authority: ctx.accounts.authority.to_account_info(),
+                    mint_authority: mint_account.clone(),
+                    mint: mint_account.clone(),
+                },
+                signer_seeds,
+            ),
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        )?;
+    }
+
+    Ok(())
+}
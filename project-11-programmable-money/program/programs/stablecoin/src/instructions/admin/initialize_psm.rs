@@ -0,0 +1,57 @@
+use crate::{
+    error::CustomError, events::ConfigUpdated, Config, BASIS_POINTS, SEED_CONFIG_ACCOUNT,
+    SEED_PSM_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct InitializePsm<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(mint::token_program = psm_token_program)]
+    pub psm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SEED_PSM_VAULT_ACCOUNT],
+        bump,
+        token::mint = psm_mint,
+        token::authority = psm_vault,
+        token::token_program = psm_token_program
+    )]
+    pub psm_vault: InterfaceAccount<'info, TokenAccount>,
+    pub psm_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// Register the reference stablecoin mint and create the PSM vault, only the config authority can invoke
+pub fn process_initialize_psm(
+    ctx: Context<InitializePsm>,
+    fee_in_bps: u64,
+    fee_out_bps: u64,
+    debt_ceiling: u64,
+) -> Result<()> {
+    require!(
+        fee_in_bps <= BASIS_POINTS && fee_out_bps <= BASIS_POINTS,
+        CustomError::InvalidFee
+    );
+
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.psm_mint = ctx.accounts.psm_mint.key();
+    config_account.psm_fee_in_bps = fee_in_bps;
+    config_account.psm_fee_out_bps = fee_out_bps;
+    config_account.psm_debt_ceiling = debt_ceiling;
+    config_account.bump_psm_vault = ctx.bumps.psm_vault;
+
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+    Ok(())
+}
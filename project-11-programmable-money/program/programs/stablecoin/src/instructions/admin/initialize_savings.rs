@@ -0,0 +1,58 @@
+use crate::{
+    error::CustomError, events::ConfigUpdated, update_stability_fee_index, Config, MAX_RATE_BPS,
+    SEED_CONFIG_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct InitializeSavings<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump,
+        token::mint = mint_account,
+        token::authority = savings_vault,
+        token::token_program = token_program
+    )]
+    pub savings_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// Create the savings vault and set the stability fee and savings rate, only the config authority can invoke
+pub fn process_initialize_savings(
+    ctx: Context<InitializeSavings>,
+    stability_fee_bps: u64,
+    savings_rate_bps: u64,
+) -> Result<()> {
+    require!(
+        stability_fee_bps <= MAX_RATE_BPS && savings_rate_bps <= MAX_RATE_BPS,
+        CustomError::InvalidRate
+    );
+
+    // The index only grows from here at the new stability fee
+    update_stability_fee_index(&mut ctx.accounts.config_account)?;
+
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.stability_fee_bps = stability_fee_bps;
+    config_account.savings_rate_bps = savings_rate_bps;
+    config_account.savings_last_accrual = Clock::get()?.unix_timestamp;
+    config_account.bump_savings_vault = ctx.bumps.savings_vault;
+
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+    Ok(())
+}
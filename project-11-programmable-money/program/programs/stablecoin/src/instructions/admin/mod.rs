@@ -2,3 +2,21 @@ pub use initialize_config::*;
 pub mod update_config;
 pub use update_config::*;
 pub mod initialize_config;
+pub use update_oracle_config::*;
+pub mod update_oracle_config;
+pub use initialize_psm::*;
+pub mod initialize_psm;
+pub use update_psm_config::*;
+pub mod update_psm_config;
+pub use shutdown::*;
+pub mod shutdown;
+pub use withdraw_transfer_fees::*;
+pub mod withdraw_transfer_fees;
+pub use update_blocklist::*;
+pub mod update_blocklist;
+pub use freeze_token_account::*;
+pub mod freeze_token_account;
+pub use initialize_savings::*;
+pub mod initialize_savings;
+pub use update_savings_config::*;
+pub mod update_savings_config;
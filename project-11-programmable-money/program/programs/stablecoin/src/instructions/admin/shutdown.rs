@@ -0,0 +1,80 @@
+use crate::{
+    error::CustomError, events::ConfigUpdated, get_sol_price_in_usd, update_stability_fee_index,
+    Config, PriceBound, SEED_CONFIG_ACCOUNT, SEED_SETTLEMENT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct Shutdown<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+    pub secondary_price_update: Option<Account<'info, PriceUpdateV2>>,
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [SEED_SETTLEMENT_ACCOUNT],
+        bump,
+    )]
+    pub settlement_account: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Emergency shutdown, only the config authority can invoke
+// Freezes minting and liquidation, and records the final SOL price used to settle all debt.
+// The price comes from the oracle, with the same checks as every other price in the protocol.
+pub fn process_shutdown(ctx: Context<Shutdown>) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+
+    // Debt is converted into lamports at this price, so use the upper bound like liquidations do
+    let settlement_price = get_sol_price_in_usd(
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
+        PriceBound::Upper,
+    )?;
+    let settlement_price =
+        u64::try_from(settlement_price).map_err(|_| error!(CustomError::MathOverflow))?;
+    require!(settlement_price > 0, CustomError::InvalidSettlementPrice);
+
+    // Keep the settlement account rent exempt from the start,
+    // so settle can pay out every lamport above the rent exempt minimum
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let rent_shortfall =
+        rent_exempt_minimum.saturating_sub(ctx.accounts.settlement_account.lamports());
+    if rent_shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.settlement_account.to_account_info(),
+                },
+            ),
+            rent_shortfall,
+        )?;
+    }
+
+    // Fees accrued up to the shutdown are still owed, the index stops growing after it
+    update_stability_fee_index(&mut ctx.accounts.config_account)?;
+
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.is_shutdown = true;
+    config_account.settlement_price = settlement_price;
+    config_account.bump_settlement_account = ctx.bumps.settlement_account;
+
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+    Ok(())
+}
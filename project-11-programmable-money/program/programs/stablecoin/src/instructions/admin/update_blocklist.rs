@@ -0,0 +1,47 @@
+use crate::{
+    error::CustomError, events::BlocklistUpdated, Blocklist, Config, MAX_BLOCKLIST_LEN,
+    SEED_BLOCKLIST_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateBlocklist<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [SEED_BLOCKLIST_ACCOUNT],
+        bump = blocklist_account.bump,
+    )]
+    pub blocklist_account: Account<'info, Blocklist>,
+}
+
+// Add or remove an address from the blocklist, only the config authority can invoke
+pub fn process_update_blocklist(
+    ctx: Context<UpdateBlocklist>,
+    address: Pubkey,
+    blocked: bool,
+) -> Result<()> {
+    let blocklist_account = &mut ctx.accounts.blocklist_account;
+
+    if blocked && !blocklist_account.is_blocked(&address) {
+        require!(
+            blocklist_account.addresses.len() < MAX_BLOCKLIST_LEN,
+            CustomError::BlocklistFull
+        );
+        blocklist_account.addresses.push(address);
+    } else if !blocked {
+        blocklist_account
+            .addresses
+            .retain(|blocked_address| *blocked_address != address);
+    }
+
+    emit!(BlocklistUpdated { address, blocked });
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::{error::CustomError, events::ConfigUpdated, Config, BASIS_POINTS, SEED_CONFIG_ACCOUNT};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateOracleConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority
+    )]
+    pub config_account: Account<'info, Config>,
+}
+
+// Update price feed safety parameters, only the config authority can invoke
+pub fn process_update_oracle_config(
+    ctx: Context<UpdateOracleConfig>,
+    max_confidence_bps: u64,
+    secondary_feed_id: Option<[u8; 32]>,
+    max_price_deviation_bps: u64,
+) -> Result<()> {
+    // Bounds above 100% would accept a confidence interval wider than the price itself
+    require!(
+        max_confidence_bps <= BASIS_POINTS && max_price_deviation_bps <= BASIS_POINTS,
+        CustomError::InvalidOracleConfig
+    );
+
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.max_confidence_bps = max_confidence_bps;
+    config_account.secondary_feed_id = secondary_feed_id;
+    config_account.max_price_deviation_bps = max_price_deviation_bps;
+
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::{error::CustomError, events::ConfigUpdated, Config, BASIS_POINTS, SEED_CONFIG_ACCOUNT};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePsmConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority
+    )]
+    pub config_account: Account<'info, Config>,
+}
+
+// Update PSM fees and debt ceiling, only the config authority can invoke
+pub fn process_update_psm_config(
+    ctx: Context<UpdatePsmConfig>,
+    fee_in_bps: u64,
+    fee_out_bps: u64,
+    debt_ceiling: u64,
+) -> Result<()> {
+    require!(
+        fee_in_bps <= BASIS_POINTS && fee_out_bps <= BASIS_POINTS,
+        CustomError::InvalidFee
+    );
+
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.psm_fee_in_bps = fee_in_bps;
+    config_account.psm_fee_out_bps = fee_out_bps;
+    config_account.psm_debt_ceiling = debt_ceiling;
+
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+    Ok(())
+}
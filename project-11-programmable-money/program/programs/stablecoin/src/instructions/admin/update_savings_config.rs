@@ -0,0 +1,57 @@
+use crate::{
+    accrue_savings_interest, error::CustomError, events::ConfigUpdated, update_stability_fee_index,
+    Config, MAX_RATE_BPS, SEED_CONFIG_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct UpdateSavingsConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump = config_account.bump_savings_vault,
+    )]
+    pub savings_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// Update the stability fee and savings rate, only the config authority can invoke
+pub fn process_update_savings_config(
+    ctx: Context<UpdateSavingsConfig>,
+    stability_fee_bps: u64,
+    savings_rate_bps: u64,
+) -> Result<()> {
+    require!(
+        stability_fee_bps <= MAX_RATE_BPS && savings_rate_bps <= MAX_RATE_BPS,
+        CustomError::InvalidRate
+    );
+
+    // Charge fees and pay out interest earned at the previous rates before changing them
+    update_stability_fee_index(&mut ctx.accounts.config_account)?;
+    accrue_savings_interest(
+        &mut ctx.accounts.config_account,
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.savings_vault,
+        &ctx.accounts.token_program,
+    )?;
+
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.stability_fee_bps = stability_fee_bps;
+    config_account.savings_rate_bps = savings_rate_bps;
+
+    emit!(ConfigUpdated::new(&ctx.accounts.config_account));
+    Ok(())
+}
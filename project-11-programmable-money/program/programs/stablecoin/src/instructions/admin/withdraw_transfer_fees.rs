@@ -0,0 +1,70 @@
+use crate::{Config, SEED_CONFIG_ACCOUNT, SEED_MINT_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::transfer_fee::{
+        harvest_withheld_tokens_to_mint, withdraw_withheld_tokens_from_mint,
+        HarvestWithheldTokensToMint, WithdrawWithheldTokensFromMint,
+    },
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTransferFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority,
+        has_one = mint_account
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// Harvest withheld transfer fees to the protocol treasury, only the config authority can invoke
+// Token accounts passed as remaining accounts are harvested into the mint first,
+// then everything withheld on the mint is withdrawn to the treasury token account.
+pub fn process_withdraw_transfer_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawTransferFees<'info>>,
+) -> Result<()> {
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let mint_account = ctx.accounts.mint_account.to_account_info();
+
+    if !ctx.remaining_accounts.is_empty() {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.clone(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.clone(),
+                    mint: mint_account.clone(),
+                },
+            ),
+            ctx.remaining_accounts.to_vec(),
+        )?;
+    }
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_MINT_ACCOUNT,
+        &[ctx.accounts.config_account.bump_mint_account],
+    ]];
+
+    withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+        token_program.clone(),
+        WithdrawWithheldTokensFromMint {
+            token_program_id: token_program.clone(),
+            mint: mint_account.clone(),
+            destination: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: mint_account,
+        },
+        signer_seeds,
+    ))
+}
@@ -0,0 +1,51 @@
+use crate::{
+    error::CustomError, events::AuctionExpired, update_stability_fee_index, Auction, Collateral,
+    Config, SEED_AUCTION_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseExpiredAuction<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(mut)]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump = auction_account.bump,
+        has_one = keeper,
+        close = keeper
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    #[account(mut)]
+    pub keeper: SystemAccount<'info>,
+}
+
+// Release a position from an auction that reached the end of its duration, anyone can invoke.
+// The collateral and debt stay on the position, the auction rent returns to the keeper.
+pub fn process_close_expired_auction(ctx: Context<CloseExpiredAuction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.auction_account.current_price(now).is_none(),
+        CustomError::AuctionActive
+    );
+
+    // No stability fee is charged for the time the position spent in the auction
+    update_stability_fee_index(&mut ctx.accounts.config_account)?;
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    collateral_account.in_auction = false;
+    collateral_account.stability_fee_index = ctx.accounts.config_account.stability_fee_index;
+
+    emit!(AuctionExpired {
+        depositor: collateral_account.depositor,
+        keeper: ctx.accounts.keeper.key(),
+        remaining_debt: collateral_account.amount_minted,
+    });
+    Ok(())
+}
@@ -0,0 +1,6 @@
+pub use close_expired_auction::*;
+pub mod close_expired_auction;
+pub use start_auction::*;
+pub mod start_auction;
+pub use take_auction::*;
+pub mod take_auction;
@@ -0,0 +1,200 @@
+use crate::{
+    accrue_stability_fee, calculate_health_factor,
+    error::CustomError,
+    events::{AuctionExpired, AuctionStarted},
+    get_sol_price_in_usd, Auction, Collateral, Config, PriceBound, AUCTION_FLOOR_BPS,
+    AUCTION_START_PREMIUM_BPS, BASIS_POINTS, SEED_AUCTION_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+    pub secondary_price_update: Option<Account<'info, PriceUpdateV2>>,
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = sol_account
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    pub sol_account: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump,
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Put an unhealthy Collateral account up for a Dutch auction, anyone can invoke.
+// An expired auction can be restarted at a fresh price, the keeper who started it keeps the rent claim.
+// If the position recovered while its auction ran, a restart releases it back to the owner instead.
+pub fn process_start_auction(ctx: Context<StartAuction>) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let in_auction = ctx.accounts.collateral_account.in_auction;
+    let auction_expired = ctx.accounts.auction_account.current_price(now).is_none();
+
+    accrue_stability_fee(
+        &mut ctx.accounts.collateral_account,
+        &mut ctx.accounts.config_account,
+    )?;
+
+    let health_factor = calculate_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
+    )?;
+
+    let action = auction_start_action(
+        in_auction,
+        auction_expired,
+        health_factor,
+        ctx.accounts.config_account.min_health_factor,
+    )?;
+    if action == AuctionStartAction::Release {
+        let collateral_account = &mut ctx.accounts.collateral_account;
+        collateral_account.in_auction = false;
+
+        emit!(AuctionExpired {
+            depositor: collateral_account.depositor,
+            keeper: ctx.accounts.auction_account.keeper,
+            remaining_debt: collateral_account.amount_minted,
+        });
+        return Ok(());
+    }
+
+    // Start above the oracle price so the auction discovers the clearing price on the way down
+    // Example: price_in_usd = 2_000_000_000 ($2.00), AUCTION_START_PREMIUM_BPS = 2_000
+    // start_price = (2_000_000_000 * 12_000) / 10_000 = 2_400_000_000 ($2.40)
+    let price_in_usd = get_sol_price_in_usd(
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
+        PriceBound::Upper,
+    )?;
+    let start_price =
+        price_in_usd * (BASIS_POINTS + AUCTION_START_PREMIUM_BPS) as u128 / BASIS_POINTS as u128;
+
+    // Stop the decay at a floor so collateral is never sold far below its value
+    // Example: price_in_usd = 2_000_000_000 ($2.00), AUCTION_FLOOR_BPS = 8_000
+    // floor_price = (2_000_000_000 * 8_000) / 10_000 = 1_600_000_000 ($1.60)
+    let floor_price = price_in_usd * AUCTION_FLOOR_BPS as u128 / BASIS_POINTS as u128;
+
+    let auction_account = &mut ctx.accounts.auction_account;
+    if auction_account.keeper == Pubkey::default() {
+        auction_account.collateral = ctx.accounts.collateral_account.key();
+        auction_account.keeper = ctx.accounts.keeper.key();
+        auction_account.bump = ctx.bumps.auction_account;
+    }
+    auction_account.start_price =
+        u64::try_from(start_price).map_err(|_| error!(CustomError::MathOverflow))?;
+    auction_account.floor_price =
+        u64::try_from(floor_price).map_err(|_| error!(CustomError::MathOverflow))?;
+    auction_account.start_time = now;
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    collateral_account.in_auction = true;
+    collateral_account.lamport_balance = ctx.accounts.sol_account.lamports();
+
+    emit!(AuctionStarted {
+        depositor: collateral_account.depositor,
+        keeper: ctx.accounts.auction_account.keeper,
+        debt: collateral_account.amount_minted,
+        lamports: collateral_account.lamport_balance,
+        start_price: ctx.accounts.auction_account.start_price,
+    });
+    Ok(())
+}
+
+// What start_auction does with a position
+#[derive(Debug, PartialEq)]
+enum AuctionStartAction {
+    Start,   // start a new auction, or restart an expired one at a fresh price
+    Release, // the expired auction's position is healthy again, hand it back to the owner
+}
+
+// Only unhealthy positions are auctioned, a running auction can't be restarted until it expires
+fn auction_start_action(
+    in_auction: bool,
+    auction_expired: bool,
+    health_factor: u64,
+    min_health_factor: u64,
+) -> Result<AuctionStartAction> {
+    if in_auction {
+        require!(auction_expired, CustomError::AuctionActive);
+        if health_factor >= min_health_factor {
+            return Ok(AuctionStartAction::Release);
+        }
+    } else {
+        require!(
+            health_factor < min_health_factor,
+            CustomError::AboveMinimumHealthFactor
+        );
+    }
+    Ok(AuctionStartAction::Start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN_HEALTH_FACTOR: u64 = 10_000;
+
+    #[test]
+    fn starts_an_auction_for_an_unhealthy_position() {
+        assert_eq!(
+            auction_start_action(false, true, 9_999, MIN_HEALTH_FACTOR).unwrap(),
+            AuctionStartAction::Start
+        );
+    }
+
+    #[test]
+    fn rejects_an_auction_for_a_healthy_position() {
+        assert_eq!(
+            auction_start_action(false, true, 10_000, MIN_HEALTH_FACTOR).unwrap_err(),
+            error!(CustomError::AboveMinimumHealthFactor)
+        );
+    }
+
+    #[test]
+    fn rejects_a_restart_while_the_auction_runs() {
+        assert_eq!(
+            auction_start_action(true, false, 9_999, MIN_HEALTH_FACTOR).unwrap_err(),
+            error!(CustomError::AuctionActive)
+        );
+    }
+
+    #[test]
+    fn restarts_an_expired_auction_for_a_position_still_unhealthy() {
+        assert_eq!(
+            auction_start_action(true, true, 9_999, MIN_HEALTH_FACTOR).unwrap(),
+            AuctionStartAction::Start
+        );
+    }
+
+    #[test]
+    fn releases_an_expired_auction_once_the_price_recovers() {
+        assert_eq!(
+            auction_start_action(true, true, 15_000, MIN_HEALTH_FACTOR).unwrap(),
+            AuctionStartAction::Release
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    burn_tokens_internal,
+    error::CustomError,
+    events::{AuctionEnded, AuctionTaken, Burned},
+    update_stability_fee_index, withdraw_sol_internal, Auction, Blocklist, Collateral, Config,
+    SEED_AUCTION_ACCOUNT, SEED_BLOCKLIST_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct TakeAuction<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [SEED_BLOCKLIST_ACCOUNT],
+        bump = blocklist_account.bump,
+    )]
+    pub blocklist_account: Box<Account<'info, Blocklist>>,
+    #[account(
+        mut,
+        has_one = depositor,
+        has_one = sol_account
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    #[account(mut)]
+    pub depositor: SystemAccount<'info>,
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump = auction_account.bump,
+        has_one = keeper
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    #[account(mut)]
+    pub keeper: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// Buy collateral from a Dutch auction at the current decayed price, paying by burning stablecoins.
+// Once the debt is covered (or the collateral runs out) the remaining collateral returns to the owner.
+pub fn process_take_auction(
+    ctx: Context<TakeAuction>,
+    amount_to_burn: u64,
+    max_price: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+    require!(
+        !ctx.accounts
+            .blocklist_account
+            .is_blocked(&ctx.accounts.taker.key()),
+        CustomError::AddressBlocked
+    );
+    require!(
+        ctx.accounts.collateral_account.in_auction,
+        CustomError::PositionNotInAuction
+    );
+    require!(amount_to_burn > 0, CustomError::InvalidAuctionAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let price = ctx
+        .accounts
+        .auction_account
+        .current_price(now)
+        .ok_or(CustomError::AuctionExpired)?;
+    require!(
+        price > 0 && price <= max_price,
+        CustomError::SlippageExceeded
+    );
+
+    // Never burn more than the outstanding debt, or buy more than the position holds
+    // Example: amount_to_burn = 500_000_000 ($0.50), price = 2_000_000_000 ($2.00)
+    // lamports = (500_000_000 * 1_000_000_000) / 2_000_000_000 = 250_000_000 (0.25 SOL)
+    let sol_balance = ctx.accounts.sol_account.lamports();
+    let mut amount_to_burn = amount_to_burn.min(ctx.accounts.collateral_account.amount_minted);
+    let mut lamports = (amount_to_burn as u128 * LAMPORTS_PER_SOL as u128 / price as u128) as u64;
+    if lamports > sol_balance {
+        lamports = sol_balance;
+        amount_to_burn =
+            (lamports as u128 * price as u128).div_ceil(LAMPORTS_PER_SOL as u128) as u64;
+    }
+    require!(lamports > 0, CustomError::InvalidAuctionAmount);
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.taker,
+        &ctx.accounts.token_program,
+        amount_to_burn,
+    )?;
+
+    withdraw_sol_internal(
+        &ctx.accounts.sol_account,
+        &ctx.accounts.taker.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.collateral_account.depositor,
+        ctx.accounts.collateral_account.bump_sol_account,
+        lamports,
+    )?;
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    collateral_account.amount_minted -= amount_to_burn;
+    ctx.accounts.config_account.total_debt -= amount_to_burn;
+    collateral_account.lamport_balance = ctx.accounts.sol_account.lamports();
+
+    emit!(Burned {
+        depositor: collateral_account.depositor,
+        burner: ctx.accounts.taker.key(),
+        amount: amount_to_burn,
+        amount_minted: collateral_account.amount_minted,
+    });
+    emit!(AuctionTaken {
+        taker: ctx.accounts.taker.key(),
+        depositor: collateral_account.depositor,
+        amount_burned: amount_to_burn,
+        lamports_bought: lamports,
+        price,
+    });
+
+    if collateral_account.amount_minted == 0 || collateral_account.lamport_balance == 0 {
+        end_auction(ctx.accounts)?;
+    }
+    Ok(())
+}
+
+// Return the remaining collateral to the owner and close the auction account to the keeper.
+// If the collateral ran out first, the uncovered debt stays on the position.
+fn end_auction(accounts: &mut TakeAuction) -> Result<()> {
+    let lamports_returned = accounts.sol_account.lamports();
+    withdraw_sol_internal(
+        &accounts.sol_account,
+        &accounts.depositor.to_account_info(),
+        &accounts.system_program,
+        &accounts.collateral_account.depositor,
+        accounts.collateral_account.bump_sol_account,
+        lamports_returned,
+    )?;
+
+    // No stability fee is charged for the time the position spent in the auction
+    update_stability_fee_index(&mut accounts.config_account)?;
+
+    let collateral_account = &mut accounts.collateral_account;
+    collateral_account.lamport_balance = 0;
+    collateral_account.in_auction = false;
+    collateral_account.stability_fee_index = accounts.config_account.stability_fee_index;
+
+    accounts
+        .auction_account
+        .close(accounts.keeper.to_account_info())?;
+
+    emit!(AuctionEnded {
+        depositor: collateral_account.depositor,
+        lamports_returned,
+        remaining_debt: collateral_account.amount_minted,
+    });
+    Ok(())
+}
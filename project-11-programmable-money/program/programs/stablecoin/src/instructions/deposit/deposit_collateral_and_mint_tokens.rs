@@ -1,5 +1,8 @@
 use crate::{
-    check_health_factor, deposit_sol_internal, mint_tokens_internal, Collateral, Config,
+    accrue_stability_fee, check_health_factor, deposit_sol_internal,
+    error::CustomError,
+    events::{CollateralDeposited, Minted},
+    mint_tokens_internal, Blocklist, Collateral, Config, SEED_BLOCKLIST_ACCOUNT,
     SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_SOL_ACCOUNT,
 };
 use anchor_lang::prelude::*;
@@ -15,11 +18,17 @@ pub struct DepositCollateralAndMintTokens<'info> {
     pub depositor: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [SEED_CONFIG_ACCOUNT],
         bump = config_account.bump,
         has_one = mint_account
     )]
     pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [SEED_BLOCKLIST_ACCOUNT],
+        bump = blocklist_account.bump,
+    )]
+    pub blocklist_account: Box<Account<'info, Blocklist>>,
     #[account(
         init_if_needed,
         payer = depositor,
@@ -37,6 +46,7 @@ pub struct DepositCollateralAndMintTokens<'info> {
     #[account(mut)]
     pub mint_account: InterfaceAccount<'info, Mint>,
     pub price_update: Account<'info, PriceUpdateV2>,
+    pub secondary_price_update: Option<Account<'info, PriceUpdateV2>>,
     #[account(
         init_if_needed,
         payer = depositor,
@@ -56,9 +66,30 @@ pub fn process_deposit_collateral_and_mint_tokens(
     amount_collateral: u64,
     amount_to_mint: u64,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+    require!(
+        !ctx.accounts
+            .blocklist_account
+            .is_blocked(&ctx.accounts.depositor.key()),
+        CustomError::AddressBlocked
+    );
+    require!(
+        !ctx.accounts.collateral_account.in_auction,
+        CustomError::PositionInAuction
+    );
+
+    accrue_stability_fee(
+        &mut ctx.accounts.collateral_account,
+        &mut ctx.accounts.config_account,
+    )?;
+
     let collateral_account = &mut ctx.accounts.collateral_account;
     collateral_account.lamport_balance = ctx.accounts.sol_account.lamports() + amount_collateral;
     collateral_account.amount_minted += amount_to_mint;
+    ctx.accounts.config_account.total_debt += amount_to_mint;
 
     if !collateral_account.is_initialized {
         collateral_account.is_initialized = true;
@@ -69,10 +100,11 @@ pub fn process_deposit_collateral_and_mint_tokens(
         collateral_account.bump_sol_account = ctx.bumps.sol_account;
     }
 
-    check_health_factor(
+    let health_factor = check_health_factor(
         &ctx.accounts.collateral_account,
         &ctx.accounts.config_account,
         &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
     )?;
 
     deposit_sol_internal(
@@ -89,5 +121,22 @@ pub fn process_deposit_collateral_and_mint_tokens(
         ctx.accounts.config_account.bump_mint_account,
         amount_to_mint,
     )?;
+
+    let collateral_account = &ctx.accounts.collateral_account;
+    if amount_collateral > 0 {
+        emit!(CollateralDeposited {
+            depositor: collateral_account.depositor,
+            amount: amount_collateral,
+            lamport_balance: collateral_account.lamport_balance,
+        });
+    }
+    if amount_to_mint > 0 {
+        emit!(Minted {
+            depositor: collateral_account.depositor,
+            amount: amount_to_mint,
+            amount_minted: collateral_account.amount_minted,
+            health_factor,
+        });
+    }
     Ok(())
 }
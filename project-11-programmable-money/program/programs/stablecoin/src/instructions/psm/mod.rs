@@ -0,0 +1,6 @@
+pub use swap_in::*;
+pub mod swap_in;
+pub use swap_out::*;
+pub mod swap_out;
+pub use utils::*;
+pub mod utils;
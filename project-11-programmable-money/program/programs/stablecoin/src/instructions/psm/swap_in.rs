@@ -0,0 +1,114 @@
+use crate::{
+    calculate_psm_fee, convert_decimals, deposit_psm_tokens_internal, error::CustomError,
+    events::SwappedIn, mint_tokens_internal, Config, MINT_DECIMALS, SEED_CONFIG_ACCOUNT,
+    SEED_PSM_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct SwapIn<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        has_one = psm_mint
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = psm_token_program)]
+    pub psm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [SEED_PSM_VAULT_ACCOUNT],
+        bump = config_account.bump_psm_vault,
+    )]
+    pub psm_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = psm_mint,
+        token::authority = user,
+        token::token_program = psm_token_program
+    )]
+    pub user_psm_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub psm_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// Deposit reference stablecoins into the PSM vault and mint stablecoins 1:1, minus the swap in fee
+pub fn process_swap_in(ctx: Context<SwapIn>, amount_in: u64) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+    require!(amount_in > 0, CustomError::InvalidSwapAmount);
+
+    // Use the amount the vault actually received, in case the reference mint charges transfer fees
+    let vault_balance_before = ctx.accounts.psm_vault.amount;
+    deposit_psm_tokens_internal(
+        &ctx.accounts.user_psm_token_account,
+        &ctx.accounts.psm_vault,
+        &ctx.accounts.psm_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.psm_token_program,
+        amount_in,
+    )?;
+    ctx.accounts.psm_vault.reload()?;
+    let amount_received = ctx.accounts.psm_vault.amount - vault_balance_before;
+
+    // Example: 1_000_000 USDC (6 decimals) in, 10 bps fee
+    // amount = 1_000_000_000, fee = 1_000_000, amount_to_mint = 999_000_000
+    let amount = convert_decimals(
+        amount_received,
+        ctx.accounts.psm_mint.decimals,
+        MINT_DECIMALS,
+    )?;
+    let fee = calculate_psm_fee(amount, ctx.accounts.config_account.psm_fee_in_bps)?;
+    let amount_to_mint = amount - fee;
+
+    let config_account = &mut ctx.accounts.config_account;
+    let psm_debt = config_account
+        .psm_debt
+        .checked_add(amount_to_mint)
+        .ok_or(CustomError::MathOverflow)?;
+    require!(
+        psm_debt <= config_account.psm_debt_ceiling,
+        CustomError::PsmDebtCeilingExceeded
+    );
+    config_account.psm_debt = psm_debt;
+
+    mint_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_mint_account,
+        amount_to_mint,
+    )?;
+
+    emit!(SwappedIn {
+        user: ctx.accounts.user.key(),
+        amount_in: amount_received,
+        amount_minted: amount_to_mint,
+        fee,
+        psm_debt: ctx.accounts.config_account.psm_debt,
+    });
+    Ok(())
+}
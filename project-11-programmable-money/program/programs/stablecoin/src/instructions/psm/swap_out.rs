@@ -0,0 +1,99 @@
+use crate::{
+    burn_tokens_internal, calculate_psm_fee, convert_decimals, error::CustomError,
+    events::SwappedOut, withdraw_psm_tokens_internal, Config, MINT_DECIMALS, SEED_CONFIG_ACCOUNT,
+    SEED_PSM_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SwapOut<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        has_one = psm_mint
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = psm_token_program)]
+    pub psm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [SEED_PSM_VAULT_ACCOUNT],
+        bump = config_account.bump_psm_vault,
+    )]
+    pub psm_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = psm_mint,
+        token::authority = user,
+        token::token_program = psm_token_program
+    )]
+    pub user_psm_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub psm_token_program: Interface<'info, TokenInterface>,
+}
+
+// Burn stablecoins and withdraw reference stablecoins 1:1 from the PSM vault, minus the swap out fee
+pub fn process_swap_out(ctx: Context<SwapOut>, amount_in: u64) -> Result<()> {
+    require!(amount_in > 0, CustomError::InvalidSwapAmount);
+
+    // Example: 1_000_000_000 stablecoins in, 10 bps fee
+    // fee = 1_000_000, amount_out = 999_000 USDC (6 decimals)
+    let fee = calculate_psm_fee(amount_in, ctx.accounts.config_account.psm_fee_out_bps)?;
+    let amount_redeemed = amount_in - fee;
+    let amount_out = convert_decimals(
+        amount_redeemed,
+        MINT_DECIMALS,
+        ctx.accounts.psm_mint.decimals,
+    )?;
+    require!(
+        amount_out <= ctx.accounts.psm_vault.amount,
+        CustomError::InsufficientPsmReserves
+    );
+
+    // swap_in records the stablecoins minted net of its fee, so only the net amount redeemed
+    // against the vault repays PSM debt, and never more than the PSM actually issued
+    let config_account = &mut ctx.accounts.config_account;
+    let debt_repaid = amount_redeemed.min(config_account.psm_debt);
+    config_account.psm_debt -= debt_repaid;
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount_in,
+    )?;
+
+    withdraw_psm_tokens_internal(
+        &ctx.accounts.psm_vault,
+        &ctx.accounts.user_psm_token_account,
+        &ctx.accounts.psm_mint,
+        &ctx.accounts.psm_token_program,
+        ctx.accounts.config_account.bump_psm_vault,
+        amount_out,
+    )?;
+
+    emit!(SwappedOut {
+        user: ctx.accounts.user.key(),
+        amount_burned: amount_in,
+        amount_out,
+        fee,
+        psm_debt: ctx.accounts.config_account.psm_debt,
+    });
+    Ok(())
+}
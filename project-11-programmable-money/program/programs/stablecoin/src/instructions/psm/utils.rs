@@ -0,0 +1,92 @@
+use crate::{error::CustomError, BASIS_POINTS, SEED_PSM_VAULT_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+// Convert an amount between mints with different decimals, rounding down
+// Example: 1_000_000 (1 USDC, 6 decimals) to 9 decimals = 1_000_000_000
+pub fn convert_decimals(amount: u64, from_decimals: u8, to_decimals: u8) -> Result<u64> {
+    let converted = if to_decimals >= from_decimals {
+        let factor = 10u128.pow((to_decimals - from_decimals) as u32);
+        amount as u128 * factor
+    } else {
+        let factor = 10u128.pow((from_decimals - to_decimals) as u32);
+        amount as u128 / factor
+    };
+
+    u64::try_from(converted).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+// PSM fee on an amount, rounding down
+// Example: amount = 1_000_000_000, fee_bps = 10, fee = (1_000_000_000 * 10) / 10_000 = 1_000_000
+pub fn calculate_psm_fee(amount: u64, fee_bps: u64) -> Result<u64> {
+    let fee = amount as u128 * fee_bps as u128 / BASIS_POINTS as u128;
+    u64::try_from(fee).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+pub fn deposit_psm_tokens_internal<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+pub fn withdraw_psm_tokens_internal<'info>(
+    psm_vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[SEED_PSM_VAULT_ACCOUNT, &[bump]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: psm_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: psm_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn psm_fee_rounds_down() {
+        assert_eq!(calculate_psm_fee(1_000_000_000, 10).unwrap(), 1_000_000);
+        assert_eq!(calculate_psm_fee(999, 10).unwrap(), 0);
+    }
+
+    #[test]
+    fn psm_fee_does_not_overflow_for_large_amounts() {
+        assert_eq!(calculate_psm_fee(u64::MAX, BASIS_POINTS).unwrap(), u64::MAX);
+        assert_eq!(calculate_psm_fee(u64::MAX, 10).unwrap(), u64::MAX / 1_000);
+    }
+}
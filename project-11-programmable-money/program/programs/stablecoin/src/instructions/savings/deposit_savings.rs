@@ -0,0 +1,102 @@
+use crate::{
+    accrue_savings_interest, calculate_savings_shares, deposit_savings_tokens_internal,
+    error::CustomError, events::SavingsDeposited, Config, SavingsPosition, SEED_CONFIG_ACCOUNT,
+    SEED_SAVINGS_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct DepositSavings<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + SavingsPosition::INIT_SPACE,
+        seeds = [SEED_SAVINGS_ACCOUNT, user.key().as_ref()],
+        bump,
+    )]
+    pub savings_position: Account<'info, SavingsPosition>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump = config_account.bump_savings_vault,
+    )]
+    pub savings_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// Deposit stablecoins into the savings vault in exchange for shares
+pub fn process_deposit_savings(ctx: Context<DepositSavings>, amount: u64) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+    require!(amount > 0, CustomError::InvalidSavingsAmount);
+
+    accrue_savings_interest(
+        &mut ctx.accounts.config_account,
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.savings_vault,
+        &ctx.accounts.token_program,
+    )?;
+
+    // Use the amount the vault actually received, in case the mint charges transfer fees
+    let vault_balance_before = ctx.accounts.savings_vault.amount;
+    deposit_savings_tokens_internal(
+        &ctx.accounts.token_account,
+        &ctx.accounts.savings_vault,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    ctx.accounts.savings_vault.reload()?;
+    let amount_received = ctx.accounts.savings_vault.amount - vault_balance_before;
+
+    let config_account = &mut ctx.accounts.config_account;
+    let shares = calculate_savings_shares(
+        amount_received,
+        config_account.savings_total_shares,
+        vault_balance_before,
+    )?;
+    require!(shares > 0, CustomError::InvalidSavingsAmount);
+    config_account.savings_total_shares = config_account
+        .savings_total_shares
+        .checked_add(shares)
+        .ok_or(CustomError::MathOverflow)?;
+
+    let savings_position = &mut ctx.accounts.savings_position;
+    if savings_position.owner == Pubkey::default() {
+        savings_position.owner = ctx.accounts.user.key();
+        savings_position.bump = ctx.bumps.savings_position;
+    }
+    savings_position.shares += shares;
+
+    emit!(SavingsDeposited {
+        user: ctx.accounts.user.key(),
+        amount: amount_received,
+        shares,
+        total_shares: ctx.accounts.config_account.savings_total_shares,
+    });
+    Ok(())
+}
@@ -0,0 +1,6 @@
+pub use deposit_savings::*;
+pub mod deposit_savings;
+pub use withdraw_savings::*;
+pub mod withdraw_savings;
+pub use utils::*;
+pub mod utils;
@@ -0,0 +1,184 @@
+use crate::{
+    error::CustomError, mint_tokens_internal, mul_div, Config, BASIS_POINTS,
+    SAVINGS_VIRTUAL_ASSETS, SAVINGS_VIRTUAL_SHARES, SECONDS_PER_YEAR, SEED_SAVINGS_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, Token2022, TokenAccount, TransferChecked,
+};
+
+// Mint savings interest into the vault since the last accrual, capped by the accrued stability fees
+pub fn accrue_savings_interest<'info>(
+    config: &mut Config,
+    mint_account: &InterfaceAccount<'info, Mint>,
+    savings_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // Interest stops at shutdown, it would mint stablecoins no longer backed by accruing stability fees
+    if !config.is_shutdown && config.savings_total_shares > 0 {
+        // Example: vault = 1_000_000_000, savings_rate_bps = 300 (3%), elapsed = 1 year
+        // interest = (1_000_000_000 * 300 * 31_536_000) / (10_000 * 31_536_000) = 30_000_000
+        let elapsed = now.saturating_sub(config.savings_last_accrual).max(0) as u128;
+        let interest = savings_vault.amount as u128 * config.savings_rate_bps as u128 * elapsed
+            / (BASIS_POINTS as u128 * SECONDS_PER_YEAR as u128);
+
+        // Savings interest is only paid out of stability fees already charged to borrowers
+        let interest = interest.min(config.accrued_stability_fees as u128) as u64;
+
+        if interest > 0 {
+            mint_tokens_internal(
+                mint_account,
+                savings_vault,
+                token_program,
+                config.bump_mint_account,
+                interest,
+            )?;
+            config.accrued_stability_fees -= interest;
+            savings_vault.reload()?;
+        }
+    }
+
+    config.savings_last_accrual = now;
+    Ok(())
+}
+
+// Convert a deposit into savings vault shares, rounding down
+// The virtual shares and assets keep the first depositor from inflating the share price with a donation,
+// at 10^6 virtual shares per unit the donation costs the attacker far more than the next deposit loses
+// Example: amount = 100, total_shares = 999_000_000, vault_balance = 1_099
+// shares = (100 * 1_000_000_000) / 1_100 = 90_909_090
+pub fn calculate_savings_shares(
+    amount: u64,
+    total_shares: u128,
+    vault_balance: u64,
+) -> Result<u128> {
+    mul_div(
+        amount as u128,
+        total_shares + SAVINGS_VIRTUAL_SHARES as u128,
+        vault_balance as u128 + SAVINGS_VIRTUAL_ASSETS as u128,
+    )
+    .ok_or(error!(CustomError::MathOverflow))
+}
+
+// Convert savings vault shares into stablecoins, rounding down, never more than the vault holds
+// Example: shares = 90_909_090, total_shares = 999_000_000, vault_balance = 1_099
+// amount = (90_909_090 * 1_100) / 1_000_000_000 = 99
+pub fn calculate_savings_amount(shares: u128, total_shares: u128, vault_balance: u64) -> u64 {
+    let amount = mul_div(
+        shares,
+        vault_balance as u128 + SAVINGS_VIRTUAL_ASSETS as u128,
+        total_shares + SAVINGS_VIRTUAL_SHARES as u128,
+    )
+    .unwrap_or(u128::MAX);
+    amount.min(vault_balance as u128) as u64
+}
+
+pub fn deposit_savings_tokens_internal<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    savings_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint_account: &InterfaceAccount<'info, Mint>,
+    authority: &Signer<'info>,
+    token_program: &Program<'info, Token2022>,
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint_account.to_account_info(),
+                to: savings_vault.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+        ),
+        amount,
+        mint_account.decimals,
+    )
+}
+
+pub fn withdraw_savings_tokens_internal<'info>(
+    savings_vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint_account: &InterfaceAccount<'info, Mint>,
+    token_program: &Program<'info, Token2022>,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[SEED_SAVINGS_VAULT_ACCOUNT, &[bump]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: savings_vault.to_account_info(),
+                mint: mint_account.to_account_info(),
+                to: to.to_account_info(),
+                authority: savings_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint_account.decimals,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_deposit_mints_virtual_shares_per_unit() {
+        assert_eq!(
+            calculate_savings_shares(100_000_000, 0, 0).unwrap(),
+            100_000_000 * SAVINGS_VIRTUAL_SHARES as u128
+        );
+    }
+
+    #[test]
+    fn deposit_and_withdraw_round_trip_rounds_down() {
+        let shares = calculate_savings_shares(100, 999_000_000, 1_099).unwrap();
+        assert_eq!(shares, 90_909_090);
+        let amount = calculate_savings_amount(shares, 999_000_000 + shares, 1_099 + 100);
+        assert!(amount <= 100);
+        assert_eq!(amount, 99);
+    }
+
+    #[test]
+    fn donation_to_empty_vault_takes_almost_nothing_from_the_next_deposit() {
+        // Attacker deposits 1 and donates 1_000_000 straight to the vault
+        let attacker_shares = calculate_savings_shares(1, 0, 0).unwrap();
+        assert_eq!(attacker_shares, SAVINGS_VIRTUAL_SHARES as u128);
+        let vault_balance = 1 + 1_000_000;
+
+        // Victim deposits less than the donation and still receives shares
+        let victim_deposit = 500_000;
+        let victim_shares =
+            calculate_savings_shares(victim_deposit, attacker_shares, vault_balance).unwrap();
+        assert!(victim_shares > 0);
+        let total_shares = attacker_shares + victim_shares;
+        let vault_balance = vault_balance + victim_deposit;
+
+        // The victim loses well under 0.1% of the deposit, the attacker loses part of the donation
+        let victim_out = calculate_savings_amount(victim_shares, total_shares, vault_balance);
+        assert!(victim_out >= victim_deposit - victim_deposit / 1_000);
+        let attacker_out = calculate_savings_amount(attacker_shares, total_shares, vault_balance);
+        assert!(attacker_out < 1 + 1_000_000);
+    }
+
+    #[test]
+    fn withdrawal_never_exceeds_vault_balance() {
+        let total_shares = 1_000 * SAVINGS_VIRTUAL_SHARES as u128;
+        assert_eq!(
+            calculate_savings_amount(total_shares * 2, total_shares, 500),
+            500
+        );
+    }
+
+    #[test]
+    fn shares_do_not_overflow_for_the_whole_supply() {
+        let shares = calculate_savings_shares(u64::MAX, 0, 0).unwrap();
+        assert_eq!(calculate_savings_amount(shares, shares, u64::MAX), u64::MAX);
+    }
+}
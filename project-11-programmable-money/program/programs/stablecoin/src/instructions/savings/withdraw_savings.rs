@@ -0,0 +1,87 @@
+use crate::{
+    accrue_savings_interest, calculate_savings_amount, error::CustomError,
+    events::SavingsWithdrawn, withdraw_savings_tokens_internal, Config, SavingsPosition,
+    SEED_CONFIG_ACCOUNT, SEED_SAVINGS_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct WithdrawSavings<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_ACCOUNT, user.key().as_ref()],
+        bump = savings_position.bump,
+        constraint = savings_position.owner == user.key()
+    )]
+    pub savings_position: Account<'info, SavingsPosition>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump = config_account.bump_savings_vault,
+    )]
+    pub savings_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// Redeem savings vault shares for stablecoins, including accrued interest
+// Withdrawals remain available after shutdown so savers can exit and settle
+pub fn process_withdraw_savings(ctx: Context<WithdrawSavings>, shares: u128) -> Result<()> {
+    require!(shares > 0, CustomError::InvalidSavingsAmount);
+    require!(
+        shares <= ctx.accounts.savings_position.shares,
+        CustomError::InsufficientSavingsShares
+    );
+
+    accrue_savings_interest(
+        &mut ctx.accounts.config_account,
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.savings_vault,
+        &ctx.accounts.token_program,
+    )?;
+
+    let config_account = &mut ctx.accounts.config_account;
+    let amount_out = calculate_savings_amount(
+        shares,
+        config_account.savings_total_shares,
+        ctx.accounts.savings_vault.amount,
+    );
+    config_account.savings_total_shares -= shares;
+    ctx.accounts.savings_position.shares -= shares;
+
+    withdraw_savings_tokens_internal(
+        &ctx.accounts.savings_vault,
+        &ctx.accounts.token_account,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_savings_vault,
+        amount_out,
+    )?;
+
+    emit!(SavingsWithdrawn {
+        user: ctx.accounts.user.key(),
+        shares,
+        amount: amount_out,
+        total_shares: ctx.accounts.config_account.savings_total_shares,
+    });
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub use settle_position::*;
+pub mod settle_position;
+pub use settle::*;
+pub mod settle;
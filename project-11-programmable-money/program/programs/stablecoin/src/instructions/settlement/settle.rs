@@ -0,0 +1,109 @@
+use crate::{
+    burn_tokens_internal, error::CustomError, events::Settled, Config, SEED_CONFIG_ACCOUNT,
+    SEED_SETTLEMENT_ACCOUNT,
+};
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Settle<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [SEED_SETTLEMENT_ACCOUNT],
+        bump = config_account.bump_settlement_account,
+    )]
+    pub settlement_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// After shutdown, burn stablecoins for lamports from the settlement account at the settlement price.
+// Claims open once settle_position has marked every Collateral account, so all claims are paid alike.
+// Only settled debt can be redeemed, stablecoins issued by the PSM are redeemed through swap_out.
+pub fn process_settle(ctx: Context<Settle>, amount_to_burn: u64) -> Result<()> {
+    let config_account = &ctx.accounts.config_account;
+    require!(config_account.is_shutdown, CustomError::ProtocolNotShutdown);
+    require!(
+        config_account.total_debt == 0,
+        CustomError::PositionsNotSettled
+    );
+    require!(
+        amount_to_burn > 0 && amount_to_burn <= config_account.settled_debt,
+        CustomError::InvalidSettlementAmount
+    );
+
+    // The settlement account keeps its rent exempt minimum, funded at shutdown
+    let available_lamports = ctx
+        .accounts
+        .settlement_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
+
+    // Example: amount_to_burn = 1_000_000_000 ($1.00), settlement_price = 2_000_000_000 ($2.00)
+    // claim = (1_000_000_000 * 1_000_000_000) / 2_000_000_000 = 500_000_000 (0.5 SOL)
+    let claim_at_settlement_price =
+        amount_to_burn as u128 * LAMPORTS_PER_SOL as u128 / config_account.settlement_price as u128;
+
+    // Undercollateralized positions leave the pool short of the settled debt,
+    // then every claim takes the same pro-rata haircut
+    // Example: available_lamports = 9_000_000_000, settled_debt = 20_000_000_000
+    // amount_to_burn = 1_000_000_000, claim = (1_000_000_000 * 9_000_000_000) / 20_000_000_000 = 450_000_000
+    let claim_pro_rata =
+        amount_to_burn as u128 * available_lamports as u128 / config_account.settled_debt as u128;
+
+    let claim = claim_at_settlement_price.min(claim_pro_rata) as u64;
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.holder,
+        &ctx.accounts.token_program,
+        amount_to_burn,
+    )?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_SETTLEMENT_ACCOUNT,
+        &[ctx.accounts.config_account.bump_settlement_account],
+    ]];
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.settlement_account.to_account_info(),
+                to: ctx.accounts.holder.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        claim,
+    )?;
+
+    ctx.accounts.config_account.settled_debt -= amount_to_burn;
+
+    emit!(Settled {
+        holder: ctx.accounts.holder.key(),
+        amount_burned: amount_to_burn,
+        lamports_claimed: claim,
+    });
+    Ok(())
+}
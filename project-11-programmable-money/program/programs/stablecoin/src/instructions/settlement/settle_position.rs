@@ -0,0 +1,73 @@
+use crate::{
+    error::CustomError, events::PositionSettled, withdraw_sol_internal, Collateral, Config,
+    SEED_CONFIG_ACCOUNT, SEED_SETTLEMENT_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+
+#[derive(Accounts)]
+pub struct SettlePosition<'info> {
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = sol_account
+    )]
+    pub collateral_account: Account<'info, Collateral>,
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [SEED_SETTLEMENT_ACCOUNT],
+        bump = config_account.bump_settlement_account,
+    )]
+    pub settlement_account: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// After shutdown, mark a Collateral account's debt to the settlement price.
+// Moves the lamports backing the debt into the settlement account, anyone can invoke.
+// Any remaining collateral stays in the sol account for the depositor to withdraw.
+pub fn process_settle_position(ctx: Context<SettlePosition>) -> Result<()> {
+    let config_account = &ctx.accounts.config_account;
+    require!(config_account.is_shutdown, CustomError::ProtocolNotShutdown);
+
+    // Example: amount_minted = 1_000_000_000 ($1.00), settlement_price = 2_000_000_000 ($2.00)
+    // lamports_owed = (1_000_000_000 * 1_000_000_000) / 2_000_000_000 = 500_000_000 (0.5 SOL)
+    let lamports_owed = (ctx.accounts.collateral_account.amount_minted as u128
+        * LAMPORTS_PER_SOL as u128
+        / config_account.settlement_price as u128) as u64;
+
+    // Undercollateralized positions contribute everything they hold
+    let amount_to_settle = lamports_owed.min(ctx.accounts.sol_account.lamports());
+
+    withdraw_sol_internal(
+        &ctx.accounts.sol_account,
+        &ctx.accounts.settlement_account.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.collateral_account.depositor,
+        ctx.accounts.collateral_account.bump_sol_account,
+        amount_to_settle,
+    )?;
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    let debt_settled = collateral_account.amount_minted;
+    collateral_account.lamport_balance = ctx.accounts.sol_account.lamports();
+    collateral_account.amount_minted = 0;
+    collateral_account.in_auction = false; // auctions are halted by shutdown, settlement replaces them
+
+    // The debt now becomes claimable from the settlement account with settle
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.total_debt -= debt_settled;
+    config_account.settled_debt += debt_settled;
+
+    emit!(PositionSettled {
+        depositor: collateral_account.depositor,
+        debt_settled,
+        lamports_settled: amount_to_settle,
+    });
+    Ok(())
+}
@@ -1,130 +1,425 @@
 use crate::{
-    error::CustomError, Collateral, Config, FEED_ID, MAXIMUM_AGE, PRICE_FEED_DECIMAL_ADJUSTMENT,
+    error::CustomError, Collateral, Config, BASIS_POINTS, FEED_ID, HEALTH_FACTOR_PRECISION,
+    MAXIMUM_AGE, MINT_DECIMALS, SECONDS_PER_YEAR, STABILITY_FEE_INDEX_PRECISION,
 };
 use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
 use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
 
 // Check health factor for Collateral account is greater than minimum required health factor
+// Returns the health factor (in basis points) for event reporting
 pub fn check_health_factor(
     collateral: &Account<Collateral>,
     config: &Account<Config>,
     price_feed: &Account<PriceUpdateV2>,
-) -> Result<()> {
-    let health_factor = calculate_health_factor(collateral, config, price_feed)?;
+    secondary_price_feed: Option<&Account<PriceUpdateV2>>,
+) -> Result<u64> {
+    let health_factor =
+        calculate_health_factor(collateral, config, price_feed, secondary_price_feed)?;
     require!(
         health_factor >= config.min_health_factor,
         CustomError::BelowMinimumHealthFactor
     );
-    Ok(())
+    Ok(health_factor)
 }
 
-// Calcuate health factor for a given Collateral account
+// Calcuate health factor (in basis points) for a given Collateral account
 pub fn calculate_health_factor(
     collateral: &Account<Collateral>,
     config: &Account<Config>,
     price_feed: &Account<PriceUpdateV2>,
+    secondary_price_feed: Option<&Account<PriceUpdateV2>>,
 ) -> Result<u64> {
     // Get the collateral value in USD
     // Assuming 1 SOL = $1.00 and $1 = 1_000_000_000
-    // Example: get_usd_value(1_000_000_000 lamports, price_feed)
+    // Example: get_usd_value(1_000_000_000 lamports, ...)
     // collateral_value_in_usd = 1_000_000_000
-    let collateral_value_in_usd = get_usd_value(&collateral.lamport_balance, price_feed)?;
-
-    // Adjust the collateral value for the liquidation threshold (require overcollateralize)
-    // Example: (1_000_000_000 * 50) / 100 = 500_000_000
-    let collateral_adjusted_for_liquidation_threshold =
-        (collateral_value_in_usd * config.liquidation_threshold) / 100;
+    let collateral_value_in_usd = get_usd_value(
+        &collateral.lamport_balance,
+        config,
+        price_feed,
+        secondary_price_feed,
+    )?;
 
-    msg!(
-        "Minted Amount : {:.9}",
-        collateral.amount_minted as f64 / 1e9
+    let health_factor = compute_health_factor(
+        collateral_value_in_usd,
+        config.liquidation_threshold,
+        collateral.amount_minted,
     );
+    Ok(health_factor)
+}
 
-    if collateral.amount_minted == 0 {
-        msg!("Health Factor Max");
-        return Ok(u64::MAX);
+// Calculate health factor scaled by HEALTH_FACTOR_PRECISION, u64::MAX if nothing is minted
+pub fn compute_health_factor(
+    collateral_value_in_usd: u64,
+    liquidation_threshold: u64,
+    amount_minted: u64,
+) -> u64 {
+    if amount_minted == 0 {
+        return u64::MAX;
     }
 
+    // Adjust the collateral value for the liquidation threshold (require overcollateralize)
+    // Example: (1_000_000_000 * 50) / 100 = 500_000_000
+    let collateral_adjusted_for_liquidation_threshold =
+        (collateral_value_in_usd as u128 * liquidation_threshold as u128) / 100;
+
     // Calculate the health factor
-    // Ratio of (adjusted collateral value) / (amount stablecoins minted)
-    // Example: 500_000_000 / 500_000_000 = 1
-    let health_factor = (collateral_adjusted_for_liquidation_threshold) / collateral.amount_minted;
+    // Ratio of (adjusted collateral value) / (amount stablecoins minted), in basis points
+    // Example: (750_000_000 * 10_000) / 500_000_000 = 15_000 (1.5)
+    let health_factor = (collateral_adjusted_for_liquidation_threshold
+        * HEALTH_FACTOR_PRECISION as u128)
+        / amount_minted as u128;
 
-    msg!("Health Factor : {}", health_factor);
-    Ok(health_factor)
+    health_factor.min(u64::MAX as u128) as u64
+}
+
+// Which side of the price confidence interval to use
+#[derive(Clone, Copy, PartialEq)]
+pub enum PriceBound {
+    Lower, // price - conf, used to value collateral
+    Upper, // price + conf, used to convert USD into collateral lamports
 }
 
-// Given lamports, return USD value based on current SOL price.
-fn get_usd_value(amount_in_lamports: &u64, price_feed: &Account<PriceUpdateV2>) -> Result<u64> {
+// Get SOL/USD price adjusted to 9 decimals, rejecting uncertain or diverging prices.
+pub fn get_sol_price_in_usd(
+    config: &Config,
+    price_feed: &Account<PriceUpdateV2>,
+    secondary_price_feed: Option<&Account<PriceUpdateV2>>,
+    bound: PriceBound,
+) -> Result<u128> {
     let feed_id = get_feed_id_from_hex(FEED_ID)?;
     let price = price_feed.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &feed_id)?;
 
     // Check price is positive
     require!(price.price > 0, CustomError::InvalidPrice);
 
-    // Adjust price to match lamports precision (9 decimals)
+    // Reject the price if the confidence interval is too wide relative to the price
+    // Example: price = 200_000_000, conf = 2_000_000, max_confidence_bps = 200
+    // 2_000_000 * 10_000 <= 200_000_000 * 200 (conf is 1% of price, max 2%)
+    require!(
+        price.conf as u128 * BASIS_POINTS as u128
+            <= price.price as u128 * config.max_confidence_bps as u128,
+        CustomError::PriceConfidenceTooWide
+    );
+
+    // Use the conservative side of the confidence interval
+    let bounded_price = match bound {
+        PriceBound::Lower => (price.price as u128)
+            .checked_sub(price.conf as u128)
+            .ok_or(CustomError::PriceConfidenceTooWide)?,
+        PriceBound::Upper => price.price as u128 + price.conf as u128,
+    };
+    require!(bounded_price > 0, CustomError::InvalidPrice);
+
+    // Adjust price to match lamports precision (9 decimals) using the Pyth exponent
     // Example: Assuming 1 SOL = $2.00
-    // price.price = 200_000_000 (from Pyth, 8 decimals)
-    // price_in_usd = 200_000_000 * 10 = 2_000_000_000 (9 decimals)
-    let price_in_usd = price.price as u128 * PRICE_FEED_DECIMAL_ADJUSTMENT;
+    // price.price = 200_000_000, price.exponent = -8
+    // price_in_usd = 200_000_000 * 10^(9 - 8) = 2_000_000_000 (9 decimals)
+    let price_in_usd = scale_price(bounded_price, price.exponent)?;
+
+    // Check the primary price against the optional secondary feed
+    if let Some(secondary_feed_id) = config.secondary_feed_id {
+        let secondary_price_feed =
+            secondary_price_feed.ok_or(CustomError::MissingSecondaryPriceFeed)?;
+        let secondary_price = secondary_price_feed.get_price_no_older_than(
+            &Clock::get()?,
+            MAXIMUM_AGE,
+            &secondary_feed_id,
+        )?;
+        require!(secondary_price.price > 0, CustomError::InvalidPrice);
+
+        // Compare mid prices, deviation in basis points of the primary price
+        // Example: primary = 2_000_000_000, secondary = 1_990_000_000, max_price_deviation_bps = 100
+        // 10_000_000 * 10_000 <= 2_000_000_000 * 100 (0.5% deviation, max 1%)
+        let primary_mid = scale_price(price.price as u128, price.exponent)?;
+        let secondary_mid = scale_price(secondary_price.price as u128, secondary_price.exponent)?;
+        require!(
+            primary_mid.abs_diff(secondary_mid) * BASIS_POINTS as u128
+                <= primary_mid * config.max_price_deviation_bps as u128,
+            CustomError::PriceDeviationTooLarge
+        );
+    }
+
+    Ok(price_in_usd)
+}
+
+// Scale a Pyth price with the given exponent to 9 decimals (MINT_DECIMALS)
+fn scale_price(price: u128, exponent: i32) -> Result<u128> {
+    let decimals = MINT_DECIMALS as i32 + exponent;
+    let factor = 10u128
+        .checked_pow(decimals.unsigned_abs())
+        .ok_or(CustomError::InvalidPrice)?;
+
+    if decimals >= 0 {
+        Ok(price.checked_mul(factor).ok_or(CustomError::InvalidPrice)?)
+    } else {
+        Ok(price / factor)
+    }
+}
+
+// Given lamports, return USD value based on current SOL price (lower bound of confidence interval).
+fn get_usd_value(
+    amount_in_lamports: &u64,
+    config: &Config,
+    price_feed: &Account<PriceUpdateV2>,
+    secondary_price_feed: Option<&Account<PriceUpdateV2>>,
+) -> Result<u64> {
+    let price_in_usd =
+        get_sol_price_in_usd(config, price_feed, secondary_price_feed, PriceBound::Lower)?;
 
     // Calculate USD value
     // Example: Convert 0.5 SOL to USD when 1 SOL = $2.00
     // amount_in_lamports = 500_000_000 (0.5 SOL)
-    // price_in_usd = 2_000_000_000 (as calculated above)
+    // price_in_usd = 2_000_000_000 (9 decimals)
     // LAMPORTS_PER_SOL = 1_000_000_000
     // amount_in_usd = (500_000_000 * 2_000_000_000) / 1_000_000_000 = 1_000_000_000 ($1.00)
     let amount_in_usd = (*amount_in_lamports as u128 * price_in_usd) / (LAMPORTS_PER_SOL as u128);
 
-    // EXAMPLE LOGS
-    // Program log: Price in USD (for 1 SOL): 136.194634200
-    // Program log: SOL Amount: 0.500000000
-    // Program log: USD Value: 68.097317100
-    // Program log: Outstanding Token Amount (Minted): 1.500000000
-    // Program log: Health Factor: 22
-    msg!("*** CONVERT USD TO SOL ***");
-    msg!("SOL/USD Price : {:.9}", price_in_usd as f64 / 1e9);
-    msg!("SOL Amount    : {:.9}", *amount_in_lamports as f64 / 1e9);
-    msg!("USD Value     : {:.9}", amount_in_usd as f64 / 1e9);
-    // msg!("Price exponent?: {}", price.exponent);
-
     Ok(amount_in_usd as u64)
 }
 
-// Given USD amount, return lamports based on current SOL price
+// Given USD amount, return lamports based on current SOL price (upper bound of confidence interval)
 pub fn get_lamports_from_usd(
     amount_in_usd: &u64,
+    config: &Config,
     price_feed: &Account<PriceUpdateV2>,
+    secondary_price_feed: Option<&Account<PriceUpdateV2>>,
 ) -> Result<u64> {
-    let feed_id = get_feed_id_from_hex(FEED_ID)?;
-    let price = price_feed.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &feed_id)?;
-
-    // Check price is positive
-    require!(price.price > 0, CustomError::InvalidPrice);
-
-    // Adjust price to match lamports precision (9 decimals)
-    // Example: Assuming 1 SOL = $2.00
-    // price.price = 200_000_000 (from Pyth, 8 decimals)
-    // price_in_usd = 200_000_000 * 10 = 2_000_000_000 (9 decimals)
-    let price_in_usd = price.price as u128 * PRICE_FEED_DECIMAL_ADJUSTMENT;
+    let price_in_usd =
+        get_sol_price_in_usd(config, price_feed, secondary_price_feed, PriceBound::Upper)?;
 
     // Calculate lamports
     // Example: Convert $0.50 to lamports when 1 SOL = $2.00
     // amount_in_usd = 500_000_000 (user input, 9 decimals for $0.50)
     // LAMPORTS_PER_SOL = 1_000_000_000
-    // price_in_usd = 2_000_000_000 (as calculated above)
+    // price_in_usd = 2_000_000_000 (9 decimals)
     // amount_in_lamports = (500_000_000 * 1_000_000_000) / 2_000_000_000 = 250_000_000 (0.25 SOL)
     let amount_in_lamports = ((*amount_in_usd as u128) * (LAMPORTS_PER_SOL as u128)) / price_in_usd;
 
-    // EXAMPLE LOGS
-    // Program log: *** CONVERT SOL TO USD ***
-    // Program log: Price in USD (for 1 SOL): 136.194634200
-    // Program log: USD Amount: 1.500000000
-    // Program log: SOL Value: 0.011013649
-    msg!("*** CONVERT SOL TO USD ***");
-    msg!("SOL/USD Price : {:.9}", price_in_usd as f64 / 1e9);
-    msg!("USD Amount    : {:.9}", *amount_in_usd as f64 / 1e9);
-    msg!("SOL Value     : {:.9}", amount_in_lamports as f64 / 1e9);
-
     Ok(amount_in_lamports as u64)
 }
+
+// Advance the cumulative stability fee index to now at the current stability fee
+// Must run before the stability fee changes, so the old rate applies up to the change and no further
+pub fn update_stability_fee_index(config: &mut Config) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // Fees stop accruing once the protocol is shut down, debt is settled at the settlement price
+    if !config.is_shutdown {
+        let elapsed = now.saturating_sub(config.stability_fee_last_update).max(0) as u64;
+        config.stability_fee_index = next_stability_fee_index(
+            config.stability_fee_index,
+            config.stability_fee_bps,
+            elapsed,
+        )?;
+    }
+
+    config.stability_fee_last_update = now;
+    Ok(())
+}
+
+// Example: index = 1_000_000_000_000_000_000 (1.0), stability_fee_bps = 500 (5%), elapsed = 1 year
+// index = 1e18 + (500 * 31_536_000 * 1e18) / (10_000 * 31_536_000) = 1_050_000_000_000_000_000
+pub fn next_stability_fee_index(index: u128, stability_fee_bps: u64, elapsed: u64) -> Result<u128> {
+    let increase =
+        stability_fee_bps as u128 * elapsed as u128 * STABILITY_FEE_INDEX_PRECISION as u128
+            / (BASIS_POINTS as u128 * SECONDS_PER_YEAR as u128);
+    Ok(index
+        .checked_add(increase)
+        .ok_or(CustomError::MathOverflow)?)
+}
+
+// Accrue the stability fee on a Collateral account's minted amount since its last accrual
+// The fee is added to the position's debt and credited to the savings rate funding pool
+pub fn accrue_stability_fee(collateral: &mut Collateral, config: &mut Config) -> Result<()> {
+    update_stability_fee_index(config)?;
+
+    let fee = calculate_stability_fee(
+        collateral.amount_minted,
+        collateral.stability_fee_index,
+        config.stability_fee_index,
+    )?;
+    if fee > 0 {
+        collateral.amount_minted = collateral
+            .amount_minted
+            .checked_add(fee)
+            .ok_or(CustomError::MathOverflow)?;
+        config.accrued_stability_fees = config
+            .accrued_stability_fees
+            .checked_add(fee)
+            .ok_or(CustomError::MathOverflow)?;
+        config.total_debt = config
+            .total_debt
+            .checked_add(fee)
+            .ok_or(CustomError::MathOverflow)?;
+    }
+
+    collateral.stability_fee_index = config.stability_fee_index;
+    Ok(())
+}
+
+// Fee owed on amount_minted for the growth of the index since the position last accrued
+// Example: amount_minted = 1_000_000_000, index grew from 1.0 to 1.05
+// fee = (1_000_000_000 * 50_000_000_000_000_000) / 1e18 = 50_000_000
+pub fn calculate_stability_fee(
+    amount_minted: u64,
+    position_index: u128,
+    current_index: u128,
+) -> Result<u64> {
+    // A position that never accrued (index 0) has nothing minted yet
+    if amount_minted == 0 || position_index == 0 {
+        return Ok(0);
+    }
+
+    let fee = (amount_minted as u128)
+        .checked_mul(current_index.saturating_sub(position_index))
+        .ok_or(CustomError::MathOverflow)?
+        / STABILITY_FEE_INDEX_PRECISION as u128;
+    u64::try_from(fee).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+// Compute a * b / denominator rounding down, with the product held in 256 bits so it can't overflow
+// Returns None if the denominator is zero or the result doesn't fit in a u128
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if let Some(product) = a.checked_mul(b) {
+        return product.checked_div(denominator);
+    }
+
+    // Multiply the 64-bit halves into a 256-bit product (high, low)
+    const LOW_MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW_MASK);
+    let (b_high, b_low) = (b >> 64, b & LOW_MASK);
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let middle = (low_low >> 64) + (high_low & LOW_MASK) + (low_high & LOW_MASK);
+    let low = (low_low & LOW_MASK) | (middle << 64);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    if high >= denominator {
+        return None;
+    }
+
+    // Long division one bit at a time, the remainder stays below the denominator
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // With a 50% liquidation threshold, 2_000 of collateral value backs exactly 1_000 of debt
+    const LIQUIDATION_THRESHOLD: u64 = 50;
+
+    #[test]
+    fn health_factor_is_max_with_nothing_minted() {
+        assert_eq!(
+            compute_health_factor(2_000, LIQUIDATION_THRESHOLD, 0),
+            u64::MAX
+        );
+        assert_eq!(compute_health_factor(0, LIQUIDATION_THRESHOLD, 0), u64::MAX);
+    }
+
+    #[test]
+    fn health_factor_is_exactly_one_at_the_threshold() {
+        assert_eq!(
+            compute_health_factor(2_000, LIQUIDATION_THRESHOLD, 1_000),
+            HEALTH_FACTOR_PRECISION
+        );
+    }
+
+    #[test]
+    fn health_factor_just_below_one() {
+        // (999 * 10_000) / 1_000 = 9_990
+        assert_eq!(
+            compute_health_factor(1_998, LIQUIDATION_THRESHOLD, 1_000),
+            9_990
+        );
+        // (1_000 * 10_000) / 1_001 = 9_990.0099..., rounded down
+        assert_eq!(
+            compute_health_factor(2_000, LIQUIDATION_THRESHOLD, 1_001),
+            9_990
+        );
+    }
+
+    #[test]
+    fn health_factor_just_above_one() {
+        // (1_001 * 10_000) / 1_000 = 10_010
+        assert_eq!(
+            compute_health_factor(2_002, LIQUIDATION_THRESHOLD, 1_000),
+            10_010
+        );
+        // (1_000 * 10_000) / 999 = 10_010.01..., rounded down
+        assert_eq!(
+            compute_health_factor(2_000, LIQUIDATION_THRESHOLD, 999),
+            10_010
+        );
+    }
+
+    #[test]
+    fn health_factor_does_not_overflow() {
+        assert_eq!(compute_health_factor(u64::MAX, 100, 1), u64::MAX);
+    }
+
+    #[test]
+    fn stability_fee_index_grows_by_the_annual_rate() {
+        let index =
+            next_stability_fee_index(STABILITY_FEE_INDEX_PRECISION as u128, 500, SECONDS_PER_YEAR)
+                .unwrap();
+        assert_eq!(index, 1_050_000_000_000_000_000);
+        assert_eq!(
+            calculate_stability_fee(1_000_000_000, STABILITY_FEE_INDEX_PRECISION as u128, index)
+                .unwrap(),
+            50_000_000
+        );
+    }
+
+    #[test]
+    fn stability_fee_rate_change_only_applies_going_forward() {
+        // Half a year at 5%, the rate is raised to 10%, then half a year at 10%
+        let start = STABILITY_FEE_INDEX_PRECISION as u128;
+        let index = next_stability_fee_index(start, 500, SECONDS_PER_YEAR / 2).unwrap();
+        let index = next_stability_fee_index(index, 1_000, SECONDS_PER_YEAR / 2).unwrap();
+
+        // 2.5% + 5%, rather than 10% for the whole year
+        assert_eq!(
+            calculate_stability_fee(1_000_000_000, start, index).unwrap(),
+            75_000_000
+        );
+    }
+
+    #[test]
+    fn stability_fee_is_zero_for_positions_that_never_accrued() {
+        let index = 2 * STABILITY_FEE_INDEX_PRECISION as u128;
+        assert_eq!(calculate_stability_fee(1_000_000_000, 0, index).unwrap(), 0);
+        assert_eq!(calculate_stability_fee(0, 1, index).unwrap(), 0);
+    }
+
+    #[test]
+    fn mul_div_matches_u128_math_when_it_fits() {
+        assert_eq!(mul_div(1_000, 3, 7), Some(428));
+        assert_eq!(
+            mul_div(u64::MAX as u128, u64::MAX as u128, 1),
+            Some((u64::MAX as u128).pow(2))
+        );
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn mul_div_handles_products_above_u128() {
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 6, 3), None);
+        assert_eq!(mul_div(1 << 100, 1 << 100, 1 << 90), Some(1 << 110));
+        assert_eq!(mul_div(u128::MAX, 3, 4), Some(u128::MAX / 4 * 3 + 2));
+    }
+}
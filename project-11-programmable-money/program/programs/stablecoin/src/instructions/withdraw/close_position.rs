@@ -0,0 +1,54 @@
+use crate::{
+    error::CustomError, events::PositionClosed, withdraw_sol_internal, Collateral,
+    SEED_COLLATERAL_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, depositor.key().as_ref()],
+        bump = collateral_account.bump,
+        has_one = depositor,
+        has_one = sol_account,
+        close = depositor
+    )]
+    pub collateral_account: Account<'info, Collateral>,
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Close a Collateral account with no outstanding debt.
+// Returns all lamports in the sol account and the Collateral account rent to the depositor.
+// Closing zeroes the account data, so a later deposit re-initializes it with is_initialized = false.
+pub fn process_close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    require!(
+        !ctx.accounts.collateral_account.in_auction,
+        CustomError::PositionInAuction
+    );
+    require!(
+        ctx.accounts.collateral_account.amount_minted == 0,
+        CustomError::OutstandingDebt
+    );
+
+    let lamports_returned = ctx.accounts.sol_account.lamports();
+    withdraw_sol_internal(
+        &ctx.accounts.sol_account,
+        &ctx.accounts.depositor.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.depositor.key(),
+        ctx.accounts.collateral_account.bump_sol_account,
+        lamports_returned,
+    )?;
+
+    emit!(PositionClosed {
+        depositor: ctx.accounts.depositor.key(),
+        lamports_returned,
+    });
+    Ok(())
+}
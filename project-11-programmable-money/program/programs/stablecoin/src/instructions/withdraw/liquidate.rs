@@ -1,6 +1,9 @@
 use crate::{
-    burn_tokens_internal, calculate_health_factor, error::CustomError, get_lamports_from_usd,
-    withdraw_sol_internal, Collateral, Config, SEED_CONFIG_ACCOUNT,
+    accrue_stability_fee, burn_tokens_internal, calculate_health_factor,
+    error::CustomError,
+    events::{Burned, Liquidated},
+    get_lamports_from_usd, withdraw_sol_internal, Blocklist, Collateral, Config,
+    SEED_BLOCKLIST_ACCOUNT, SEED_CONFIG_ACCOUNT,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
@@ -12,12 +15,19 @@ pub struct Liquidate<'info> {
     pub liquidator: Signer<'info>,
 
     pub price_update: Account<'info, PriceUpdateV2>,
+    pub secondary_price_update: Option<Account<'info, PriceUpdateV2>>,
     #[account(
+        mut,
         seeds = [SEED_CONFIG_ACCOUNT],
         bump = config_account.bump,
         has_one = mint_account
     )]
     pub config_account: Account<'info, Config>,
+    #[account(
+        seeds = [SEED_BLOCKLIST_ACCOUNT],
+        bump = blocklist_account.bump,
+    )]
+    pub blocklist_account: Box<Account<'info, Blocklist>>,
     #[account(
         mut,
         has_one = sol_account
@@ -39,11 +49,36 @@ pub struct Liquidate<'info> {
 }
 
 // https://github.com/Cyfrin/foundry-defi-stablecoin-cu/blob/main/src/DSCEngine.sol#L215
-pub fn process_liquidate(ctx: Context<Liquidate>, amount_to_burn: u64) -> Result<()> {
+pub fn process_liquidate(
+    ctx: Context<Liquidate>,
+    amount_to_burn: u64,
+    min_lamports_out: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.config_account.is_shutdown,
+        CustomError::ProtocolShutdown
+    );
+    require!(
+        !ctx.accounts
+            .blocklist_account
+            .is_blocked(&ctx.accounts.liquidator.key()),
+        CustomError::AddressBlocked
+    );
+    require!(
+        !ctx.accounts.collateral_account.in_auction,
+        CustomError::PositionInAuction
+    );
+
+    accrue_stability_fee(
+        &mut ctx.accounts.collateral_account,
+        &mut ctx.accounts.config_account,
+    )?;
+
     let health_factor = calculate_health_factor(
         &ctx.accounts.collateral_account,
         &ctx.accounts.config_account,
         &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
     )?;
 
     require!(
@@ -51,14 +86,34 @@ pub fn process_liquidate(ctx: Context<Liquidate>, amount_to_burn: u64) -> Result
         CustomError::AboveMinimumHealthFactor
     );
 
-    let lamports = get_lamports_from_usd(&amount_to_burn, &ctx.accounts.price_update)?;
+    // Limit how much of the position can be closed in a single liquidation
+    // Example: amount_minted = 1_000_000_000, max_close_factor = 50
+    // max_amount_to_burn = (1_000_000_000 * 50) / 100 = 500_000_000
+    require!(amount_to_burn > 0, CustomError::InvalidLiquidationAmount);
+    let max_amount_to_burn = (ctx.accounts.collateral_account.amount_minted as u128
+        * ctx.accounts.config_account.max_close_factor as u128
+        / 100) as u64;
+    require!(
+        amount_to_burn <= max_amount_to_burn,
+        CustomError::AboveMaximumCloseFactor
+    );
+
+    let lamports = get_lamports_from_usd(
+        &amount_to_burn,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
+    )?;
     let liquidation_bonus = lamports * ctx.accounts.config_account.liquidation_bonus / 100;
-    let amount_to_liquidate = lamports + liquidation_bonus;
 
-    msg!("*** LIQUIDATION ***");
-    msg!("Bonus {}%", ctx.accounts.config_account.liquidation_bonus);
-    msg!("Bonus Amount  : {:.9}", liquidation_bonus as f64 / 1e9);
-    msg!("SOL Liquidated: {:.9}", amount_to_liquidate as f64 / 1e9);
+    // Never seize more lamports than the position holds
+    let amount_to_liquidate =
+        (lamports + liquidation_bonus).min(ctx.accounts.collateral_account.lamport_balance);
+
+    require!(
+        amount_to_liquidate >= min_lamports_out,
+        CustomError::SlippageExceeded
+    );
 
     withdraw_sol_internal(
         &ctx.accounts.sol_account,
@@ -80,12 +135,35 @@ pub fn process_liquidate(ctx: Context<Liquidate>, amount_to_burn: u64) -> Result
     let collateral_account = &mut ctx.accounts.collateral_account;
     collateral_account.lamport_balance = ctx.accounts.sol_account.lamports();
     collateral_account.amount_minted -= amount_to_burn;
+    ctx.accounts.config_account.total_debt -= amount_to_burn;
 
-    // Optional, logs new health factor
-    calculate_health_factor(
+    // Liquidation must leave the position healthier than before
+    let new_health_factor = calculate_health_factor(
         &ctx.accounts.collateral_account,
         &ctx.accounts.config_account,
         &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
     )?;
+    require!(
+        new_health_factor > health_factor,
+        CustomError::HealthFactorNotImproved
+    );
+
+    let collateral_account = &ctx.accounts.collateral_account;
+    emit!(Burned {
+        depositor: collateral_account.depositor,
+        burner: ctx.accounts.liquidator.key(),
+        amount: amount_to_burn,
+        amount_minted: collateral_account.amount_minted,
+    });
+    emit!(Liquidated {
+        liquidator: ctx.accounts.liquidator.key(),
+        depositor: collateral_account.depositor,
+        amount_burned: amount_to_burn,
+        lamports_seized: amount_to_liquidate,
+        liquidation_bonus: amount_to_liquidate.saturating_sub(lamports),
+        health_factor_before: health_factor,
+        health_factor_after: new_health_factor,
+    });
     Ok(())
 }
@@ -2,5 +2,7 @@ pub use redeem_collateral_and_burn_tokens::*;
 pub mod redeem_collateral_and_burn_tokens;
 pub use liquidate::*;
 pub mod liquidate;
+pub use close_position::*;
+pub mod close_position;
 pub use utils::*;
 pub mod utils;
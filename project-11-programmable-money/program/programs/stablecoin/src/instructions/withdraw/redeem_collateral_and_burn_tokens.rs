@@ -1,6 +1,8 @@
 use crate::{
-    burn_tokens_internal, check_health_factor, withdraw_sol_internal, Collateral, Config,
-    SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT,
+    accrue_stability_fee, burn_tokens_internal, check_health_factor,
+    error::CustomError,
+    events::{Burned, Redeemed},
+    withdraw_sol_internal, Collateral, Config, SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
@@ -12,7 +14,9 @@ pub struct RedeemCollateralAndBurnTokens<'info> {
     pub depositor: Signer<'info>,
 
     pub price_update: Account<'info, PriceUpdateV2>,
+    pub secondary_price_update: Option<Account<'info, PriceUpdateV2>>,
     #[account(
+        mut,
         seeds = [SEED_CONFIG_ACCOUNT],
         bump = config_account.bump,
         has_one = mint_account
@@ -42,14 +46,26 @@ pub fn process_redeem_collateral_and_burn_tokens(
     amount_collateral: u64,
     amount_to_burn: u64,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.collateral_account.in_auction,
+        CustomError::PositionInAuction
+    );
+
+    accrue_stability_fee(
+        &mut ctx.accounts.collateral_account,
+        &mut ctx.accounts.config_account,
+    )?;
+
     let collateral_account = &mut ctx.accounts.collateral_account;
     collateral_account.lamport_balance = ctx.accounts.sol_account.lamports() - amount_collateral;
     collateral_account.amount_minted -= amount_to_burn;
+    ctx.accounts.config_account.total_debt -= amount_to_burn;
 
-    check_health_factor(
+    let health_factor = check_health_factor(
         &ctx.accounts.collateral_account,
         &ctx.accounts.config_account,
         &ctx.accounts.price_update,
+        ctx.accounts.secondary_price_update.as_ref(),
     )?;
 
     burn_tokens_internal(
@@ -69,5 +85,22 @@ pub fn process_redeem_collateral_and_burn_tokens(
         amount_collateral,
     )?;
 
+    let collateral_account = &ctx.accounts.collateral_account;
+    if amount_to_burn > 0 {
+        emit!(Burned {
+            depositor: collateral_account.depositor,
+            burner: ctx.accounts.depositor.key(),
+            amount: amount_to_burn,
+            amount_minted: collateral_account.amount_minted,
+        });
+    }
+    if amount_collateral > 0 {
+        emit!(Redeemed {
+            depositor: collateral_account.depositor,
+            amount: amount_collateral,
+            lamport_balance: collateral_account.lamport_balance,
+            health_factor,
+        });
+    }
     Ok(())
 }
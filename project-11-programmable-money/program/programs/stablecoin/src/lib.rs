@@ -4,6 +4,7 @@ use instructions::*;
 use state::*;
 mod constants;
 mod error;
+mod events;
 mod instructions;
 mod state;
 
@@ -13,14 +14,93 @@ declare_id!("6DjiD8tQhJ9ZS3WZrwNubfoBRBrqfWacNR3bXBQ7ir91");
 pub mod stablecoin {
     use super::*;
 
-    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
-        process_initialize_config(ctx)
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        metadata: Option<TokenMetadataArgs>,
+        enable_permanent_delegate: bool,
+        transfer_fee: Option<TransferFeeArgs>,
+    ) -> Result<()> {
+        process_initialize_config(ctx, metadata, enable_permanent_delegate, transfer_fee)
     }
 
     pub fn update_config(ctx: Context<UpdateConfig>, min_health_factor: u64) -> Result<()> {
         process_update_config(ctx, min_health_factor)
     }
 
+    pub fn update_oracle_config(
+        ctx: Context<UpdateOracleConfig>,
+        max_confidence_bps: u64,
+        secondary_feed_id: Option<[u8; 32]>,
+        max_price_deviation_bps: u64,
+    ) -> Result<()> {
+        process_update_oracle_config(
+            ctx,
+            max_confidence_bps,
+            secondary_feed_id,
+            max_price_deviation_bps,
+        )
+    }
+
+    pub fn initialize_psm(
+        ctx: Context<InitializePsm>,
+        fee_in_bps: u64,
+        fee_out_bps: u64,
+        debt_ceiling: u64,
+    ) -> Result<()> {
+        process_initialize_psm(ctx, fee_in_bps, fee_out_bps, debt_ceiling)
+    }
+
+    pub fn update_psm_config(
+        ctx: Context<UpdatePsmConfig>,
+        fee_in_bps: u64,
+        fee_out_bps: u64,
+        debt_ceiling: u64,
+    ) -> Result<()> {
+        process_update_psm_config(ctx, fee_in_bps, fee_out_bps, debt_ceiling)
+    }
+
+    pub fn initialize_savings(
+        ctx: Context<InitializeSavings>,
+        stability_fee_bps: u64,
+        savings_rate_bps: u64,
+    ) -> Result<()> {
+        process_initialize_savings(ctx, stability_fee_bps, savings_rate_bps)
+    }
+
+    pub fn update_savings_config(
+        ctx: Context<UpdateSavingsConfig>,
+        stability_fee_bps: u64,
+        savings_rate_bps: u64,
+    ) -> Result<()> {
+        process_update_savings_config(ctx, stability_fee_bps, savings_rate_bps)
+    }
+
+    pub fn withdraw_transfer_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawTransferFees<'info>>,
+    ) -> Result<()> {
+        process_withdraw_transfer_fees(ctx)
+    }
+
+    pub fn update_blocklist(
+        ctx: Context<UpdateBlocklist>,
+        address: Pubkey,
+        blocked: bool,
+    ) -> Result<()> {
+        process_update_blocklist(ctx, address, blocked)
+    }
+
+    pub fn freeze_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+        process_freeze_account(ctx)
+    }
+
+    pub fn thaw_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+        process_thaw_account(ctx)
+    }
+
+    pub fn shutdown(ctx: Context<Shutdown>) -> Result<()> {
+        process_shutdown(ctx)
+    }
+
     pub fn deposit_collateral_and_mint(
         ctx: Context<DepositCollateralAndMintTokens>,
         amount_collateral: u64,
@@ -37,7 +117,55 @@ pub mod stablecoin {
         process_redeem_collateral_and_burn_tokens(ctx, amount_collateral, amount_to_burn)
     }
 
-    pub fn liquidate(ctx: Context<Liquidate>, amount_to_burn: u64) -> Result<()> {
-        process_liquidate(ctx, amount_to_burn)
+    pub fn liquidate(
+        ctx: Context<Liquidate>,
+        amount_to_burn: u64,
+        min_lamports_out: u64,
+    ) -> Result<()> {
+        process_liquidate(ctx, amount_to_burn, min_lamports_out)
+    }
+
+    pub fn start_auction(ctx: Context<StartAuction>) -> Result<()> {
+        process_start_auction(ctx)
+    }
+
+    pub fn take_auction(
+        ctx: Context<TakeAuction>,
+        amount_to_burn: u64,
+        max_price: u64,
+    ) -> Result<()> {
+        process_take_auction(ctx, amount_to_burn, max_price)
+    }
+
+    pub fn close_expired_auction(ctx: Context<CloseExpiredAuction>) -> Result<()> {
+        process_close_expired_auction(ctx)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        process_close_position(ctx)
+    }
+
+    pub fn swap_in(ctx: Context<SwapIn>, amount_in: u64) -> Result<()> {
+        process_swap_in(ctx, amount_in)
+    }
+
+    pub fn swap_out(ctx: Context<SwapOut>, amount_in: u64) -> Result<()> {
+        process_swap_out(ctx, amount_in)
+    }
+
+    pub fn deposit_savings(ctx: Context<DepositSavings>, amount: u64) -> Result<()> {
+        process_deposit_savings(ctx, amount)
+    }
+
+    pub fn withdraw_savings(ctx: Context<WithdrawSavings>, shares: u128) -> Result<()> {
+        process_withdraw_savings(ctx, shares)
+    }
+
+    pub fn settle_position(ctx: Context<SettlePosition>) -> Result<()> {
+        process_settle_position(ctx)
+    }
+
+    pub fn settle(ctx: Context<Settle>, amount_to_burn: u64) -> Result<()> {
+        process_settle(ctx, amount_to_burn)
     }
 }
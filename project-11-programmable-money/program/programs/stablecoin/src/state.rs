@@ -1,16 +1,19 @@
+use crate::{AUCTION_DURATION, MAX_BLOCKLIST_LEN};
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct Collateral {
-    pub depositor: Pubkey,     // depositor wallet address
-    pub sol_account: Pubkey,   // depositor pda collateral account (deposit SOL to this account)
-    pub token_account: Pubkey, // depositor ata token account (mint stablecoins to this account)
+    pub depositor: Pubkey,         // depositor wallet address
+    pub sol_account: Pubkey,       // depositor pda collateral account (deposit SOL to this account)
+    pub token_account: Pubkey,     // depositor ata token account (mint stablecoins to this account)
     pub lamport_balance: u64, // current lamport balance of depositor sol_account (for health check calculation)
     pub amount_minted: u64, // current amount stablecoins minted, base unit adjusted for decimal precision (for health check calculation)
     pub bump: u8,           // store bump seed for this collateral account PDA
     pub bump_sol_account: u8, // store bump seed for the  sol_account PDA
     pub is_initialized: bool, // indicate if account data has already been initialized (for check to prevent overriding certain fields)
+    pub stability_fee_index: u128, // config stability fee index when fees were last accrued onto amount_minted
+    pub in_auction: bool, // position is being sold through a Dutch auction, owner actions are paused
 }
 
 #[account]
@@ -20,7 +23,118 @@ pub struct Config {
     pub mint_account: Pubkey,       // the stablecoin mint address, which is a PDA
     pub liquidation_threshold: u64, // determines how much extra collateral is required
     pub liquidation_bonus: u64,     // % bonus lamports to liquidator for liquidating an account
-    pub min_health_factor: u64, // minimum health factor, if below min then Collateral account can be liquidated
-    pub bump: u8,               // store bump seed for this config account
-    pub bump_mint_account: u8,  // store bump seed for the stablecoin mint account PDA
+    pub max_close_factor: u64, // max % of an account's minted amount that can be burned in a single liquidation
+    pub min_health_factor: u64, // minimum health factor in basis points (10_000 = 1.0), if below min then Collateral account can be liquidated
+    pub max_confidence_bps: u64, // max price confidence interval relative to price, in basis points
+    pub secondary_feed_id: Option<[u8; 32]>, // optional secondary price feed checked for deviation against the primary feed
+    pub max_price_deviation_bps: u64, // max deviation between primary and secondary feed prices, in basis points
+    pub psm_mint: Pubkey, // reference stablecoin mint for the peg stability module (default if not registered)
+    pub psm_fee_in_bps: u64, // fee on swap_in (reference -> stablecoin), in basis points
+    pub psm_fee_out_bps: u64, // fee on swap_out (stablecoin -> reference), in basis points
+    pub psm_debt_ceiling: u64, // max amount of stablecoins outstanding minted through the PSM
+    pub psm_debt: u64,    // current amount of stablecoins outstanding minted through the PSM
+    pub bump_psm_vault: u8, // store bump seed for the PSM vault token account PDA
+    pub is_shutdown: bool, // emergency shutdown, freezes minting and liquidation
+    pub settlement_price: u64, // final SOL/USD price (9 decimals) recorded at shutdown, debt is settled at this price
+    pub bump_settlement_account: u8, // store bump seed for the settlement sol account PDA
+    pub total_debt: u64, // stablecoins minted against collateral and not yet repaid or settled, across all positions
+    pub settled_debt: u64, // debt marked to the settlement price by settle_position and not yet redeemed with settle
+    pub stability_fee_bps: u64, // annual stability fee charged on minted debt, in basis points
+    pub savings_rate_bps: u64, // annual savings rate paid to savings vault depositors, in basis points
+    pub accrued_stability_fees: u64, // stability fees accrued and not yet paid out as savings interest
+    pub stability_fee_index: u128, // cumulative stability fee per unit of debt, scaled by STABILITY_FEE_INDEX_PRECISION
+    pub stability_fee_last_update: i64, // unix timestamp the stability fee index was last advanced
+    pub savings_total_shares: u128, // total shares issued by the savings vault
+    pub savings_last_accrual: i64, // unix timestamp savings interest was last accrued into the vault
+    pub bump_savings_vault: u8,    // store bump seed for the savings vault token account PDA
+    pub bump: u8,                  // store bump seed for this config account
+    pub bump_mint_account: u8,     // store bump seed for the stablecoin mint account PDA
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct SavingsPosition {
+    pub owner: Pubkey, // depositor wallet address
+    pub shares: u128,  // savings vault shares owned by the depositor
+    pub bump: u8,      // store bump seed for this savings position PDA
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Auction {
+    pub collateral: Pubkey, // Collateral account being auctioned
+    pub keeper: Pubkey,     // address that started the auction, receives the rent back when it ends
+    pub start_price: u64, // SOL/USD price (9 decimals) at the start of the auction, including the premium
+    pub floor_price: u64, // SOL/USD price (9 decimals) the auction decays to, a fraction of the oracle price
+    pub start_time: i64,  // unix timestamp the auction started, price decays from here
+    pub bump: u8,         // store bump seed for this auction account PDA
+}
+
+impl Auction {
+    // Price decays linearly from start_price to floor_price over AUCTION_DURATION, then the auction expires
+    // Example: start_price = 2_400_000_000, floor_price = 1_600_000_000, elapsed = 900, duration = 3_600
+    // price = 1_600_000_000 + (800_000_000 * (3_600 - 900)) / 3_600 = 2_200_000_000
+    pub fn current_price(&self, now: i64) -> Option<u64> {
+        let elapsed = now.saturating_sub(self.start_time).max(0);
+        if elapsed >= AUCTION_DURATION {
+            return None;
+        }
+
+        let remaining = (AUCTION_DURATION - elapsed) as u128;
+        let decay_range = self.start_price.saturating_sub(self.floor_price) as u128;
+        Some(self.floor_price + (decay_range * remaining / AUCTION_DURATION as u128) as u64)
+    }
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Blocklist {
+    #[max_len(MAX_BLOCKLIST_LEN)]
+    pub addresses: Vec<Pubkey>, // addresses barred from minting and liquidating (sanctions compliance)
+    pub bump: u8, // store bump seed for this blocklist account PDA
+}
+
+impl Blocklist {
+    pub fn is_blocked(&self, address: &Pubkey) -> bool {
+        self.addresses.contains(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auction() -> Auction {
+        Auction {
+            collateral: Pubkey::default(),
+            keeper: Pubkey::default(),
+            start_price: 2_400_000_000,
+            floor_price: 1_600_000_000,
+            start_time: 1_000,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn auction_starts_at_start_price() {
+        assert_eq!(auction().current_price(1_000), Some(2_400_000_000));
+    }
+
+    #[test]
+    fn auction_price_decays_linearly() {
+        assert_eq!(auction().current_price(1_900), Some(2_200_000_000));
+    }
+
+    #[test]
+    fn auction_price_never_goes_below_floor() {
+        let price = auction()
+            .current_price(1_000 + AUCTION_DURATION - 1)
+            .unwrap();
+        assert!(price >= 1_600_000_000);
+    }
+
+    #[test]
+    fn auction_expires_after_duration() {
+        assert_eq!(auction().current_price(1_000 + AUCTION_DURATION), None);
+    }
 }
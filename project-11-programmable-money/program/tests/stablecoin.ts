@@ -2,6 +2,20 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Stablecoin } from "../target/types/stablecoin";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  transferChecked,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+// Health factor is expressed in basis points, 10_000 = 1.0
+const HEALTH_FACTOR_PRECISION = 10_000;
 
 describe("stablecoin", () => {
   const provider = anchor.AnchorProvider.env();
@@ -24,15 +38,82 @@ describe("stablecoin", () => {
     [Buffer.from("collateral"), wallet.publicKey.toBuffer()],
     program.programId
   );
+  const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
 
   it("Is initialized!", async () => {
+    // Token-2022 mint with on-chain metadata, permanent delegate and a 0.1% transfer fee
+    const metadata = { name: "USD Stablecoin", symbol: "USDS", uri: "" };
+    const enablePermanentDelegate = true;
+    const transferFee = {
+      transferFeeBasisPoints: 10,
+      maximumFee: new anchor.BN(1_000_000_000),
+    };
     const tx = await program.methods
-      .initializeConfig()
+      .initializeConfig(metadata, enablePermanentDelegate, transferFee)
       .accounts({})
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
 
+  // Reject prices with a confidence interval above 2%, no secondary feed
+  it("Update Oracle Config", async () => {
+    const maxConfidenceBps = 200;
+    const maxPriceDeviationBps = 100;
+    const tx = await program.methods
+      .updateOracleConfig(
+        new anchor.BN(maxConfidenceBps),
+        null,
+        new anchor.BN(maxPriceDeviationBps)
+      )
+      .accounts({})
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    console.log("Your transaction signature", tx);
+  });
+
+  it("Rejects Oracle Bounds Above 100%", async () => {
+    try {
+      await program.methods
+        .updateOracleConfig(new anchor.BN(10_001), null, new anchor.BN(100))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("A confidence bound above 100% should be rejected");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "InvalidOracleConfig");
+    }
+  });
+
+  // With a zero confidence bound any live price is too uncertain to use
+  it("Rejects Prices With A Confidence Interval Too Wide", async () => {
+    await program.methods
+      .updateOracleConfig(new anchor.BN(0), null, new anchor.BN(100))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .depositCollateralAndMint(
+          new anchor.BN(1_000_000_000),
+          new anchor.BN(1_000_000_000)
+        )
+        .accounts({
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Minting with a too uncertain price should fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "PriceConfidenceTooWide");
+    } finally {
+      await program.methods
+        .updateOracleConfig(new anchor.BN(200), null, new anchor.BN(100))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
   it("Deposit Collateral and Mint USDS", async () => {
     const amountCollateral = 1_000_000_000;
     const amountToMint = 1_000_000_000;
@@ -41,7 +122,10 @@ describe("stablecoin", () => {
         new anchor.BN(amountCollateral),
         new anchor.BN(amountToMint)
       )
-      .accounts({ priceUpdate: solUsdPriceFeedAccount })
+      .accounts({
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
@@ -54,7 +138,10 @@ describe("stablecoin", () => {
         new anchor.BN(amountCollateral),
         new anchor.BN(amountToBurn)
       )
-      .accounts({ priceUpdate: solUsdPriceFeedAccount })
+      .accounts({
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
@@ -62,26 +149,692 @@ describe("stablecoin", () => {
   // Increase minimum health threshold to test liquidate
   it("Update Config", async () => {
     const tx = await program.methods
-      .updateConfig(new anchor.BN(100))
+      .updateConfig(new anchor.BN(100 * HEALTH_FACTOR_PRECISION))
       .accounts({})
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
 
+  it("Liquidate Rejects Amount Above Maximum Close Factor", async () => {
+    // 50% of the 500_000_000 minted is 250_000_000
+    try {
+      await program.methods
+        .liquidate(new anchor.BN(250_000_001), new anchor.BN(0))
+        .accounts({
+          collateralAccount,
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Liquidating above the maximum close factor should fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "AboveMaximumCloseFactor");
+    }
+  });
+
+  it("Liquidate Rejects Lamports Below Minimum Out", async () => {
+    try {
+      await program.methods
+        .liquidate(
+          new anchor.BN(250_000_000),
+          new anchor.BN(anchor.web3.LAMPORTS_PER_SOL)
+        )
+        .accounts({
+          collateralAccount,
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Liquidating below the minimum lamports out should fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "SlippageExceeded");
+    }
+  });
+
+  // Burning a single base unit seizes no lamports and is too small
+  // to move the health factor by a whole basis point
+  it("Liquidate Rejects Liquidation That Does Not Improve Health Factor", async () => {
+    try {
+      await program.methods
+        .liquidate(new anchor.BN(1), new anchor.BN(0))
+        .accounts({
+          collateralAccount,
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("A liquidation that does not improve health should fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "HealthFactorNotImproved");
+    }
+  });
+
   it("Liquidate", async () => {
-    const amountToBurn = 500_000_000;
+    // Max close factor allows burning at most 50% of the 500_000_000 minted
+    const amountToBurn = 250_000_000;
+    const minLamportsOut = 0;
     const tx = await program.methods
-      .liquidate(new anchor.BN(amountToBurn))
-      .accounts({ collateralAccount, priceUpdate: solUsdPriceFeedAccount })
+      .liquidate(new anchor.BN(amountToBurn), new anchor.BN(minLamportsOut))
+      .accounts({
+        collateralAccount,
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
 
   it("Update Config", async () => {
     const tx = await program.methods
-      .updateConfig(new anchor.BN(1))
+      .updateConfig(new anchor.BN(HEALTH_FACTOR_PRECISION))
       .accounts({})
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
+
+  // Peg stability module with a USDC-like reference mint (6 decimals)
+  let psmMint: anchor.web3.PublicKey;
+  let userPsmTokenAccount: anchor.web3.PublicKey;
+
+  it("Initialize PSM", async () => {
+    psmMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      6
+    );
+    userPsmTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        psmMint,
+        wallet.publicKey
+      )
+    ).address;
+    await mintTo(
+      connection,
+      wallet.payer,
+      psmMint,
+      userPsmTokenAccount,
+      wallet.publicKey,
+      1_000_000_000
+    );
+
+    const feeInBps = 10;
+    const feeOutBps = 10;
+    const debtCeiling = 1_000_000_000_000;
+    const tx = await program.methods
+      .initializePsm(
+        new anchor.BN(feeInBps),
+        new anchor.BN(feeOutBps),
+        new anchor.BN(debtCeiling)
+      )
+      .accounts({ psmMint, psmTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    console.log("Your transaction signature", tx);
+  });
+
+  it("Swap In and Swap Out through PSM", async () => {
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const balanceBefore = (
+      await getAccount(
+        connection,
+        tokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+
+    // 1 USDC in, 1 USDS (9 decimals) out minus 10 bps fee
+    await program.methods
+      .swapIn(new anchor.BN(1_000_000))
+      .accounts({ userPsmTokenAccount, psmTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+
+    const balanceAfterSwapIn = (
+      await getAccount(
+        connection,
+        tokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+    assert.equal(balanceAfterSwapIn - balanceBefore, BigInt(999_000_000));
+
+    let config = await program.account.config.fetch(configAccount, "confirmed");
+    assert.equal(config.psmDebt.toNumber(), 999_000_000);
+
+    // 0.5 USDS in, 0.5 USDC out minus 10 bps fee
+    const psmBalanceBefore = (await getAccount(connection, userPsmTokenAccount))
+      .amount;
+    await program.methods
+      .swapOut(new anchor.BN(500_000_000))
+      .accounts({ userPsmTokenAccount, psmTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+
+    const psmBalanceAfter = (await getAccount(connection, userPsmTokenAccount))
+      .amount;
+    assert.equal(psmBalanceAfter - psmBalanceBefore, BigInt(499_500));
+
+    // Only the 499_500_000 redeemed net of the fee repays PSM debt
+    config = await program.account.config.fetch(configAccount, "confirmed");
+    assert.equal(config.psmDebt.toNumber(), 499_500_000);
+  });
+
+  it("Withdraw Transfer Fees", async () => {
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const recipient = anchor.web3.Keypair.generate();
+    const recipientTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        mintAccount,
+        recipient.publicKey,
+        false,
+        "confirmed",
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+
+    // 10 bps of the transfer is withheld on the recipient token account
+    await transferChecked(
+      connection,
+      wallet.payer,
+      treasuryTokenAccount,
+      mintAccount,
+      recipientTokenAccount,
+      wallet.publicKey,
+      100_000_000,
+      9,
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+    const balanceBefore = (
+      await getAccount(
+        connection,
+        treasuryTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+
+    await program.methods
+      .withdrawTransferFees()
+      .accounts({ treasuryTokenAccount })
+      .remainingAccounts([
+        { pubkey: recipientTokenAccount, isSigner: false, isWritable: true },
+      ])
+      .rpc({ commitment: "confirmed" });
+
+    const balanceAfter = (
+      await getAccount(
+        connection,
+        treasuryTokenAccount,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+    assert.equal(balanceAfter - balanceBefore, BigInt(100_000));
+  });
+
+  it("Dutch Auction Liquidation", async () => {
+    const depositor = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: depositor.publicKey,
+          lamports: anchor.web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const [depositorCollateral] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), depositor.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Mint at a health factor of ~1.5, then raise the minimum to 2.0
+    const priceUpdate =
+      await pythSolanaReceiver.receiver.account.priceUpdateV2.fetch(
+        solUsdPriceFeedAccount
+      );
+    const { price, exponent } = priceUpdate.priceMessage;
+    const amountCollateral = new anchor.BN(5_000_000);
+    const collateralValue = price
+      .mul(new anchor.BN(10).pow(new anchor.BN(9 + exponent)))
+      .mul(amountCollateral)
+      .div(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL));
+    const amountToMint = collateralValue.muln(50).divn(100).muln(2).divn(3);
+
+    await program.methods
+      .depositCollateralAndMint(amountCollateral, amountToMint)
+      .accounts({
+        depositor: depositor.publicKey,
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
+      .signers([depositor])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .updateConfig(new anchor.BN(2 * HEALTH_FACTOR_PRECISION))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .startAuction()
+        .accounts({
+          collateralAccount: depositorCollateral,
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .rpc({ commitment: "confirmed" });
+    } finally {
+      await program.methods
+        .updateConfig(new anchor.BN(HEALTH_FACTOR_PRECISION))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+    }
+
+    let collateral = await program.account.collateral.fetch(
+      depositorCollateral,
+      "confirmed"
+    );
+    assert.isTrue(collateral.inAuction);
+
+    // The price decays to a floor, and an auction can only be closed once it expires
+    const [auctionAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), depositorCollateral.toBuffer()],
+      program.programId
+    );
+    const auction = await program.account.auction.fetch(
+      auctionAccount,
+      "confirmed"
+    );
+    assert.isTrue(auction.floorPrice.gtn(0));
+    assert.isTrue(auction.floorPrice.lt(auction.startPrice));
+    assert.isTrue(auction.keeper.equals(wallet.publicKey));
+
+    try {
+      await program.methods
+        .closeExpiredAuction()
+        .accounts({ collateralAccount: depositorCollateral })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Expected close expired auction to fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "AuctionActive");
+    }
+
+    // Cover the whole debt, the remaining collateral returns to the depositor
+    const depositorBalanceBefore = await connection.getBalance(
+      depositor.publicKey,
+      "confirmed"
+    );
+    await program.methods
+      .takeAuction(amountToMint, new anchor.BN("18446744073709551615"))
+      .accounts({ collateralAccount: depositorCollateral })
+      .rpc({ commitment: "confirmed" });
+
+    collateral = await program.account.collateral.fetch(
+      depositorCollateral,
+      "confirmed"
+    );
+    assert.isFalse(collateral.inAuction);
+    assert.equal(collateral.amountMinted.toNumber(), 0);
+    const depositorBalanceAfter = await connection.getBalance(
+      depositor.publicKey,
+      "confirmed"
+    );
+    assert.isAbove(depositorBalanceAfter, depositorBalanceBefore);
+  });
+
+  it("Initialize Savings", async () => {
+    const stabilityFeeBps = 500;
+    const savingsRateBps = 300;
+    const tx = await program.methods
+      .initializeSavings(
+        new anchor.BN(stabilityFeeBps),
+        new anchor.BN(savingsRateBps)
+      )
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    console.log("Your transaction signature", tx);
+  });
+
+  it("Deposit and Withdraw Savings", async () => {
+    const [savingsPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("savings"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // 0.1 USDS in, the vault receives the amount minus the 10 bps transfer fee
+    const tx = await program.methods
+      .depositSavings(new anchor.BN(100_000_000))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    const txResult = await connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    const events = [...eventParser.parseLogs(txResult.meta.logMessages)];
+    const deposited = events.find((e) => e.name === "savingsDeposited");
+    // The first deposit is issued 10^6 shares per unit received
+    const expectedShares = new anchor.BN(99_900_000).muln(1_000_000);
+    assert.equal(deposited.data.amount.toNumber(), 99_900_000);
+    assert.isTrue(deposited.data.shares.eq(expectedShares));
+
+    let position = await program.account.savingsPosition.fetch(
+      savingsPosition,
+      "confirmed"
+    );
+    assert.isTrue(position.shares.eq(expectedShares));
+
+    await program.methods
+      .withdrawSavings(position.shares)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    position = await program.account.savingsPosition.fetch(
+      savingsPosition,
+      "confirmed"
+    );
+    assert.equal(position.shares.toNumber(), 0);
+  });
+
+  it("Blocklisted Address Cannot Mint", async () => {
+    const blocked = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: blocked.publicKey,
+          lamports: anchor.web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    await program.methods
+      .updateBlocklist(blocked.publicKey, true)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .depositCollateralAndMint(
+          new anchor.BN(500_000_000),
+          new anchor.BN(1_000_000)
+        )
+        .accounts({
+          depositor: blocked.publicKey,
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .signers([blocked])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Blocklisted address should not be able to mint");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "AddressBlocked");
+    }
+
+    await program.methods
+      .updateBlocklist(blocked.publicKey, false)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Freeze and Thaw Account", async () => {
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .freezeAccount()
+      .accounts({ tokenAccount })
+      .rpc({ commitment: "confirmed" });
+    let account = await getAccount(
+      connection,
+      tokenAccount,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.isTrue(account.isFrozen);
+
+    await program.methods
+      .thawAccount()
+      .accounts({ tokenAccount })
+      .rpc({ commitment: "confirmed" });
+    account = await getAccount(
+      connection,
+      tokenAccount,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.isFalse(account.isFrozen);
+  });
+
+  // A health factor of 1.5 used to be truncated to 1,
+  // check both sides of a fractional minimum health factor
+  it("Enforces Fractional Minimum Health Factor", async () => {
+    const depositor = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: depositor.publicKey,
+          lamports: 2 * anchor.web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    // Mint against 1 SOL so the position lands at a health factor of ~1.5
+    // USD value (9 decimals) = price * 10^(9 + exponent),
+    // adjusted by the 50% liquidation threshold
+    const priceUpdate =
+      await pythSolanaReceiver.receiver.account.priceUpdateV2.fetch(
+        solUsdPriceFeedAccount
+      );
+    const { price, exponent } = priceUpdate.priceMessage;
+    const amountCollateral = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL);
+    const collateralValue = price.mul(
+      new anchor.BN(10).pow(new anchor.BN(9 + exponent))
+    );
+    const amountToMint = collateralValue.muln(50).divn(100).muln(2).divn(3);
+
+    await program.methods
+      .updateConfig(new anchor.BN(1.4 * HEALTH_FACTOR_PRECISION))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .depositCollateralAndMint(amountCollateral, amountToMint)
+      .accounts({
+        depositor: depositor.publicKey,
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
+      .signers([depositor])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .updateConfig(new anchor.BN(1.6 * HEALTH_FACTOR_PRECISION))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .depositCollateralAndMint(new anchor.BN(0), new anchor.BN(1))
+        .accounts({
+          depositor: depositor.publicKey,
+          priceUpdate: solUsdPriceFeedAccount,
+          secondaryPriceUpdate: null,
+        })
+        .signers([depositor])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Minting below the minimum health factor should fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "BelowMinimumHealthFactor");
+    } finally {
+      await program.methods
+        .updateConfig(new anchor.BN(HEALTH_FACTOR_PRECISION))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("Deposit Emits Events", async () => {
+    const amountCollateral = 100_000_000;
+    const amountToMint = 1_000_000;
+    const tx = await program.methods
+      .depositCollateralAndMint(
+        new anchor.BN(amountCollateral),
+        new anchor.BN(amountToMint)
+      )
+      .accounts({
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const txResult = await connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    const events = [...eventParser.parseLogs(txResult.meta.logMessages)];
+
+    const deposited = events.find((e) => e.name === "collateralDeposited");
+    assert.equal(deposited.data.amount.toNumber(), amountCollateral);
+    const minted = events.find((e) => e.name === "minted");
+    assert.equal(minted.data.amount.toNumber(), amountToMint);
+    assert.isAtLeast(
+      minted.data.healthFactor.toNumber(),
+      HEALTH_FACTOR_PRECISION
+    );
+  });
+
+  // Shutdown is irreversible, keep these tests last
+  it("Shutdown", async () => {
+    // The settlement price is read from the oracle
+    const tx = await program.methods
+      .shutdown()
+      .accounts({
+        priceUpdate: solUsdPriceFeedAccount,
+        secondaryPriceUpdate: null,
+      })
+      .rpc({ commitment: "confirmed" });
+    console.log("Your transaction signature", tx);
+
+    const config = await program.account.config.fetch(
+      configAccount,
+      "confirmed"
+    );
+    assert.isTrue(config.isShutdown);
+    assert.isTrue(config.settlementPrice.gtn(0));
+  });
+
+  it("Rejects Settle Before All Positions Are Settled", async () => {
+    try {
+      await program.methods
+        .settle(new anchor.BN(100_000_000))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("Expected settle to fail");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "PositionsNotSettled");
+    }
+  });
+
+  it("Settle Position", async () => {
+    // Claims open only once every position with debt is marked
+    const positions = await program.account.collateral.all();
+    for (const position of positions) {
+      if (position.account.amountMinted.isZero()) continue;
+      const tx = await program.methods
+        .settlePosition()
+        .accounts({ collateralAccount: position.publicKey })
+        .rpc({ commitment: "confirmed" });
+      console.log("Your transaction signature", tx);
+    }
+
+    const collateral = await program.account.collateral.fetch(
+      collateralAccount
+    );
+    assert(collateral.amountMinted.isZero());
+    const config = await program.account.config.fetch(
+      configAccount,
+      "confirmed"
+    );
+    assert(config.totalDebt.isZero());
+  });
+
+  // Debt was cleared by settlement, so the position can be closed
+  it("Close Position", async () => {
+    const tx = await program.methods
+      .closePosition()
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    console.log("Your transaction signature", tx);
+
+    const collateral = await connection.getAccountInfo(collateralAccount);
+    assert.isNull(collateral);
+  });
+
+  it("Settle", async () => {
+    const amountToBurn = 100_000_000;
+    const before = await program.account.config.fetch(
+      configAccount,
+      "confirmed"
+    );
+    const tx = await program.methods
+      .settle(new anchor.BN(amountToBurn))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    console.log("Your transaction signature", tx);
+
+    const after = await program.account.config.fetch(
+      configAccount,
+      "confirmed"
+    );
+    assert.equal(
+      before.settledDebt.sub(after.settledDebt).toNumber(),
+      amountToBurn
+    );
+  });
 });
@@ -4,3 +4,14 @@ use anchor_lang::prelude::*;
 pub const SEED: &str = "anchor";
 
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
+
+pub const BASIS_POINTS: u16 = 10_000;
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000;
+
+pub const MAX_ORDERS_PER_SIDE: usize = 64;
+
+// Liquidity that stays in every pool forever, so LP token prices can't be inflated from an empty pool
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+pub const LP_MINT_DECIMALS: u8 = 6;
+
+pub const MAX_BASKET_LEGS: usize = 4;
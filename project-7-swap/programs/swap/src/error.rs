@@ -2,6 +2,87 @@ use anchor_lang::prelude::*;
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Custom error message")]
-    CustomError,
+    // Keeps error code 6000 taken so the codes after it stay the same
+    #[msg("Reserved error code, never returned")]
+    Reserved,
+    #[msg("Take amount must be greater than zero and at most the remaining wanted amount")]
+    InvalidTakeAmount,
+    #[msg("Take amount is too small to receive any offered tokens")]
+    TakeAmountTooSmall,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("Offer has not expired yet")]
+    OfferNotExpired,
+    #[msg("Offer can only be taken by the allowed taker")]
+    TakerNotAllowed,
+    #[msg("Fee exceeds the maximum fee")]
+    FeeTooHigh,
+    #[msg("Base lot size must be greater than zero")]
+    InvalidLotSize,
+    #[msg(
+        "Transfer fee mints are not supported on markets, pools or as the wanted token of an offer"
+    )]
+    TransferFeeNotSupported,
+    #[msg("Order price and quantity must be greater than zero")]
+    InvalidOrder,
+    #[msg("Order would match a resting order on the other side of the book")]
+    OrderWouldCross,
+    #[msg("This side of the order book is full")]
+    OrderBookFull,
+    #[msg("Order not found")]
+    OrderNotFound,
+    #[msg("No resting orders matched")]
+    NoOrdersFilled,
+    #[msg("Open orders account does not match the resting order")]
+    InvalidOpenOrders,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Liquidity amounts must be greater than zero")]
+    InvalidLiquidityAmount,
+    #[msg("Deposit is too small to mint any liquidity")]
+    InsufficientLiquidity,
+    #[msg("Swap amount must be greater than zero")]
+    InvalidSwapAmount,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("Basket offers must offer and want between one and four different tokens")]
+    InvalidBasketSize,
+    #[msg("Basket amounts must be greater than zero")]
+    InvalidBasketAmount,
+    #[msg("Remaining accounts do not match the basket")]
+    InvalidBasketAccounts,
+    #[msg("Collection offers must want exactly one NFT")]
+    InvalidCollectionOffer,
+    #[msg("Token B does not match the offer")]
+    InvalidTokenMintB,
+    #[msg("Collection offers need the metadata of the NFT being sent")]
+    MissingNftMetadata,
+    #[msg("Token B is not an NFT")]
+    NotAnNft,
+    #[msg("NFT is not a verified member of the wanted collection")]
+    NftNotInCollection,
+    #[msg("Maker token account B is not the maker's associated token account")]
+    InvalidMakerTokenAccountB,
+    #[msg("Takes charged a fee need the fee recipient's token B account")]
+    MissingFeeRecipientTokenAccount,
+    #[msg("Minimum order quantity must be greater than zero")]
+    InvalidMinOrderQuantity,
+    #[msg("Order quantity is below the market's minimum order quantity")]
+    OrderTooSmall,
+    #[msg("Basket vaults must be empty and owned by the basket offer")]
+    InvalidBasketVault,
+    #[msg("Offered and wanted amounts must be greater than zero")]
+    InvalidOfferAmount,
+    #[msg("Token A and token B must be different mints")]
+    IdenticalTokenMints,
+    #[msg("Vault does not hold enough token A for this take")]
+    InsufficientVaultBalance,
+    #[msg("New expiry must be later than the offer's current expiry")]
+    ExpiryNotExtended,
+    #[msg("The final take needs the maker's token A account to return tokens sent to the vault")]
+    MissingMakerTokenAccountA,
+    #[msg("Each token can only appear once on each side of a basket offer")]
+    DuplicateBasketMint,
 }
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub id: u64,
+    pub maker: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_a_offered_amount: u64,
+    pub token_b_wanted_amount: u64,
+}
+
+// Emitted for every fill, partial or complete
+#[event]
+pub struct OfferTaken {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub fee: u64,
+    pub is_filled: bool,
+}
+
+// Emitted when the maker amends the price, vault balance or expiry of an offer
+#[event]
+pub struct OfferUpdated {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub token_a_offered_amount: u64,
+    pub token_b_wanted_amount: u64,
+    pub expires_at: Option<i64>,
+}
+
+// Emitted when the maker refunds an offer, or anyone closes an expired one
+#[event]
+pub struct OfferCancelled {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub token_a_refunded_amount: u64,
+}
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, Pool, MINIMUM_LIQUIDITY};
+
+use super::transfer_tokens;
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        has_one = lp_mint,
+        seeds = [b"pool", token_mint_a.key().as_ref(), token_mint_b.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, mint::token_program = lp_token_program)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program_a
+    )]
+    pub provider_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program_b
+    )]
+    pub provider_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = lp_token_program
+    )]
+    pub provider_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// The first deposit sets the pool's price. Later deposits take tokens at the pool's ratio,
+// up to `max_amount_a` and `max_amount_b`, and must mint at least `min_lp_amount`.
+pub fn deposit_liquidity(
+    context: Context<AddLiquidity>,
+    max_amount_a: u64,
+    max_amount_b: u64,
+    min_lp_amount: u64,
+) -> Result<()> {
+    require!(
+        max_amount_a > 0 && max_amount_b > 0,
+        ErrorCode::InvalidLiquidityAmount
+    );
+
+    let pool = &context.accounts.pool;
+    let (lp_amount, amount_a, amount_b) = pool
+        .deposit_amounts(
+            context.accounts.vault_a.amount,
+            context.accounts.vault_b.amount,
+            max_amount_a,
+            max_amount_b,
+        )
+        .ok_or(ErrorCode::InsufficientLiquidity)?;
+    require!(lp_amount > 0, ErrorCode::InsufficientLiquidity);
+    require!(lp_amount >= min_lp_amount, ErrorCode::SlippageExceeded);
+
+    transfer_tokens(
+        &context.accounts.provider_token_account_a,
+        &context.accounts.vault_a,
+        &amount_a,
+        &context.accounts.token_mint_a,
+        &context.accounts.provider,
+        &context.accounts.token_program_a,
+    )?;
+
+    transfer_tokens(
+        &context.accounts.provider_token_account_b,
+        &context.accounts.vault_b,
+        &amount_b,
+        &context.accounts.token_mint_b,
+        &context.accounts.provider,
+        &context.accounts.token_program_b,
+    )?;
+
+    let seeds = &[
+        b"pool",
+        context.accounts.token_mint_a.to_account_info().key.as_ref(),
+        context.accounts.token_mint_b.to_account_info().key.as_ref(),
+        &[pool.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    let mint_accounts_options = MintTo {
+        mint: context.accounts.lp_mint.to_account_info(),
+        to: context.accounts.provider_lp_token_account.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        context.accounts.lp_token_program.to_account_info(),
+        mint_accounts_options,
+        &signer_seeds,
+    );
+
+    mint_to(cpi_context, lp_amount)?;
+
+    // The first deposit also accounts for the locked minimum liquidity
+    let pool = &mut context.accounts.pool;
+    if pool.lp_supply == 0 {
+        pool.lp_supply = MINIMUM_LIQUIDITY;
+    }
+    pool.lp_supply += lp_amount;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Market, OpenOrders, Order, Side};
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = market,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+}
+
+// Removes the order from the book and credits its locked funds back to the owner's free balance
+pub fn remove_order_and_unlock_funds(
+    context: Context<CancelOrder>,
+    side: Side,
+    order_id: u64,
+) -> Result<()> {
+    let mut market = context.accounts.market.load_mut()?;
+    let open_orders = &mut context.accounts.open_orders;
+
+    let index = market
+        .find_order(side, order_id, &open_orders.key())
+        .ok_or(ErrorCode::OrderNotFound)?;
+    let order = market.remove_order(side, index);
+
+    unlock_order_funds(open_orders, &market, side, &order)
+}
+
+// Credits what a resting order had locked back to its owner's free balance
+pub fn unlock_order_funds(
+    open_orders: &mut OpenOrders,
+    market: &Market,
+    side: Side,
+    order: &Order,
+) -> Result<()> {
+    match side {
+        Side::Bid => {
+            open_orders.quote_free = open_orders
+                .quote_free
+                .checked_add(
+                    market
+                        .quote_amount_for(order.quantity, order.price)
+                        .ok_or(ErrorCode::MathOverflow)?,
+                )
+                .ok_or(ErrorCode::MathOverflow)?
+        }
+        Side::Ask => {
+            open_orders.base_free = open_orders
+                .base_free
+                .checked_add(
+                    market
+                        .base_amount_for(order.quantity)
+                        .ok_or(ErrorCode::MathOverflow)?,
+                )
+                .ok_or(ErrorCode::MathOverflow)?
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, Market, ANCHOR_DISCRIMINATOR};
+
+use super::has_transfer_fee;
+
+#[derive(Accounts)]
+pub struct CreateMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mint::token_program = base_token_program)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = quote_token_program)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + std::mem::size_of::<Market>(),
+        seeds = [b"market", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Market balances are tracked in exact amounts, so transfer fee mints can't be listed.
+// The minimum order quantity, in base lots, stops dust orders from filling up the book.
+pub fn save_market(
+    context: Context<CreateMarket>,
+    base_lot_size: u64,
+    min_order_quantity: u64,
+) -> Result<()> {
+    require!(base_lot_size > 0, ErrorCode::InvalidLotSize);
+    require!(min_order_quantity > 0, ErrorCode::InvalidMinOrderQuantity);
+    require!(
+        !has_transfer_fee(&context.accounts.base_mint)?
+            && !has_transfer_fee(&context.accounts.quote_mint)?,
+        ErrorCode::TransferFeeNotSupported
+    );
+
+    let mut market = context.accounts.market.load_init()?;
+    market.base_mint = context.accounts.base_mint.key();
+    market.quote_mint = context.accounts.quote_mint.key();
+    market.base_lot_size = base_lot_size;
+    market.min_order_quantity = min_order_quantity;
+    market.bump = context.bumps.market;
+    Ok(())
+}
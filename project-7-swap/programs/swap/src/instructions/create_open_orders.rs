@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{Market, OpenOrders, ANCHOR_DISCRIMINATOR};
+
+#[derive(Accounts)]
+pub struct CreateOpenOrders<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + OpenOrders::INIT_SPACE,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn save_open_orders(context: Context<CreateOpenOrders>) -> Result<()> {
+    context.accounts.open_orders.set_inner(OpenOrders {
+        market: context.accounts.market.key(),
+        owner: context.accounts.owner.key(),
+        base_free: 0,
+        quote_free: 0,
+        bump: context.bumps.open_orders,
+    });
+    Ok(())
+}
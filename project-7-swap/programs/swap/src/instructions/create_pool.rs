@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, Pool, ANCHOR_DISCRIMINATOR, LP_MINT_DECIMALS, MAX_FEE_BASIS_POINTS};
+
+use super::has_transfer_fee;
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + Pool::INIT_SPACE,
+        seeds = [b"pool", token_mint_a.key().as_ref(), token_mint_b.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::authority = pool,
+        mint::token_program = lp_token_program
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Reserves are read from the vault balances, so transfer fee mints can't be pooled
+pub fn save_pool(context: Context<CreatePool>, fee_basis_points: u16) -> Result<()> {
+    require!(
+        fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        ErrorCode::FeeTooHigh
+    );
+    require!(
+        !has_transfer_fee(&context.accounts.token_mint_a)?
+            && !has_transfer_fee(&context.accounts.token_mint_b)?,
+        ErrorCode::TransferFeeNotSupported
+    );
+
+    context.accounts.pool.set_inner(Pool {
+        token_mint_a: context.accounts.token_mint_a.key(),
+        token_mint_b: context.accounts.token_mint_b.key(),
+        lp_mint: context.accounts.lp_mint.key(),
+        fee_basis_points,
+        lp_supply: 0,
+        bump: context.bumps.pool,
+        lp_mint_bump: context.bumps.lp_mint,
+    });
+    Ok(())
+}
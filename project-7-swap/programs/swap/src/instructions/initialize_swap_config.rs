@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ErrorCode, program::Swap, SwapConfig, ANCHOR_DISCRIMINATOR, MAX_FEE_BASIS_POINTS,
+};
+
+// Only the program's upgrade authority can create the config, so nobody can front-run the admin role
+#[derive(Accounts)]
+pub struct InitializeSwapConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ANCHOR_DISCRIMINATOR + SwapConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub swap_config: Account<'info, SwapConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Swap>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn save_swap_config(
+    context: Context<InitializeSwapConfig>,
+    fee_basis_points: u16,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    require!(
+        fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        ErrorCode::FeeTooHigh
+    );
+
+    context.accounts.swap_config.set_inner(SwapConfig {
+        admin: context.accounts.admin.key(),
+        fee_basis_points,
+        fee_recipient,
+        bump: context.bumps.swap_config,
+    });
+    Ok(())
+}
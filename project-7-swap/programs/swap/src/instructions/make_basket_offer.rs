@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, BasketLeg, BasketOffer, ANCHOR_DISCRIMINATOR, MAX_BASKET_LEGS};
+
+use super::{require_associated_token_account, token_program_for, transfer_tokens};
+
+// The remaining accounts are, for each offered token in order:
+// its mint, the maker's token account, and its vault (created here unless it already exists)
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct MakeBasketOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = ANCHOR_DISCRIMINATOR + BasketOffer::INIT_SPACE,
+        seeds = [b"basket_offer", maker.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub basket_offer: Account<'info, BasketOffer>,
+
+    pub system_program: Program<'info, System>,
+    #[account(address = anchor_spl::token::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_2022_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Creates a vault for each offered token and moves the maker's tokens into it.
+// As with single offers, the basket records what each vault actually received.
+pub fn send_offered_tokens_to_vaults<'info>(
+    context: Context<'_, '_, 'info, 'info, MakeBasketOffer<'info>>,
+    id: u64,
+    offered_amounts: Vec<u64>,
+    wanted: Vec<BasketLeg>,
+) -> Result<()> {
+    require!(
+        is_valid_basket_size(offered_amounts.len()) && is_valid_basket_size(wanted.len()),
+        ErrorCode::InvalidBasketSize
+    );
+    require!(
+        offered_amounts.iter().all(|amount| *amount > 0) && wanted.iter().all(|leg| leg.amount > 0),
+        ErrorCode::InvalidBasketAmount
+    );
+    require!(
+        has_unique_mints(wanted.iter().map(|leg| leg.mint)),
+        ErrorCode::DuplicateBasketMint
+    );
+    require!(
+        context.remaining_accounts.len() == offered_amounts.len() * 3,
+        ErrorCode::InvalidBasketAccounts
+    );
+    require!(
+        has_unique_mints(
+            context
+                .remaining_accounts
+                .chunks(3)
+                .map(|leg_accounts| leg_accounts[0].key())
+        ),
+        ErrorCode::DuplicateBasketMint
+    );
+
+    let basket_offer_key = context.accounts.basket_offer.key();
+    let mut offered = Vec::with_capacity(offered_amounts.len());
+
+    for (leg_accounts, amount) in context.remaining_accounts.chunks(3).zip(offered_amounts) {
+        let [mint_info, maker_token_account_info, vault_info] = leg_accounts else {
+            return err!(ErrorCode::InvalidBasketAccounts);
+        };
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        let token_program = token_program_for(
+            mint_info,
+            &context.accounts.token_program,
+            &context.accounts.token_2022_program,
+        )?;
+        require_associated_token_account(
+            vault_info,
+            &basket_offer_key,
+            &mint.key(),
+            token_program.key,
+        )?;
+
+        // Anyone can create the vault ahead of the maker, which must not block the offer
+        create_idempotent(CpiContext::new(
+            context.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: context.accounts.maker.to_account_info(),
+                associated_token: vault_info.clone(),
+                authority: context.accounts.basket_offer.to_account_info(),
+                mint: mint_info.clone(),
+                system_program: context.accounts.system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+
+        let maker_token_account =
+            InterfaceAccount::<TokenAccount>::try_from(maker_token_account_info)?;
+        let mut vault = InterfaceAccount::<TokenAccount>::try_from(vault_info)?;
+        require!(
+            vault.owner == basket_offer_key && vault.amount == 0,
+            ErrorCode::InvalidBasketVault
+        );
+
+        transfer_tokens(
+            &maker_token_account,
+            &vault,
+            &amount,
+            &mint,
+            &context.accounts.maker,
+            token_program,
+        )?;
+
+        vault.reload()?;
+        offered.push(BasketLeg {
+            mint: mint.key(),
+            amount: vault.amount,
+        });
+    }
+
+    context.accounts.basket_offer.set_inner(BasketOffer {
+        id,
+        maker: context.accounts.maker.key(),
+        offered,
+        wanted,
+        bump: context.bumps.basket_offer,
+    });
+    Ok(())
+}
+
+fn is_valid_basket_size(legs: usize) -> bool {
+    (1..=MAX_BASKET_LEGS).contains(&legs)
+}
+
+fn has_unique_mints(mints: impl Iterator<Item = Pubkey>) -> bool {
+    let mints: Vec<Pubkey> = mints.collect();
+    mints
+        .iter()
+        .enumerate()
+        .all(|(index, mint)| !mints[..index].contains(mint))
+}
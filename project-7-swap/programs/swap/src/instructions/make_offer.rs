@@ -1,12 +1,23 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::{Offer, ANCHOR_DISCRIMINATOR};
+use crate::{error::ErrorCode, events::OfferMade, Offer, ANCHOR_DISCRIMINATOR};
+
+use super::{associated_token_account_len, has_transfer_fee, transfer_tokens};
 
-use super::transfer_tokens;
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OfferOptions {
+    pub expires_at: Option<i64>,
+    pub allowed_taker: Option<Pubkey>,
+    pub is_collection_offer: bool,
+    pub reimburse_taker_rent: bool,
+}
 
 #[derive(Accounts)]
 #[instruction(id: u64)]
@@ -14,20 +25,26 @@ pub struct MakeOffer<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
 
-    #[account(mint::token_program = token_program)]
+    #[account(mint::token_program = token_program_a)]
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
-    #[account(mint::token_program = token_program)]
+    #[account(
+        mint::token_program = token_program_b,
+        constraint = token_mint_b.key() != token_mint_a.key() @ ErrorCode::IdenticalTokenMints
+    )]
     pub token_mint_b: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = token_mint_a,
         associated_token::authority = maker,
-        associated_token::token_program = token_program
+        associated_token::token_program = token_program_a
     )]
     pub maker_token_account_a: InterfaceAccount<'info, TokenAccount>,
 
+    // Reusing the id of an open offer fails here, as the offer account already exists.
+    // Anchor creates init accounts before any other check runs, so the error clients see is the
+    // System Program's AccountAlreadyInUse rather than one of this program's errors.
     #[account(
         init,
         payer = maker,
@@ -42,15 +59,24 @@ pub struct MakeOffer<'info> {
         payer = maker,
         associated_token::mint = token_mint_a,
         associated_token::authority = offer,
-        associated_token::token_program = token_program
+        associated_token::token_program = token_program_a
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+pub fn validate_offer(token_a_offered_amount: u64, token_b_wanted_amount: u64) -> Result<()> {
+    require!(
+        token_a_offered_amount > 0 && token_b_wanted_amount > 0,
+        ErrorCode::InvalidOfferAmount
+    );
+    Ok(())
+}
+
 pub fn send_offered_tokens_to_vault(
     context: &Context<MakeOffer>,
     token_a_offered_amount: u64,
@@ -61,18 +87,93 @@ pub fn send_offered_tokens_to_vault(
         &token_a_offered_amount,
         &context.accounts.token_mint_a,
         &context.accounts.maker,
-        &context.accounts.token_program,
+        &context.accounts.token_program_a,
     )
 }
 
-pub fn save_offer(context: Context<MakeOffer>, id: u64, token_b_wanted_amount: u64) -> Result<()> {
+// The offer records what the vault actually received, which is less than what the maker sent
+// when token A is a Token-2022 mint with a transfer fee
+pub fn save_offer(
+    context: Context<MakeOffer>,
+    id: u64,
+    token_b_wanted_amount: u64,
+    options: OfferOptions,
+) -> Result<()> {
+    if let Some(expires_at) = options.expires_at {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidExpiry
+        );
+    }
+
+    // The maker would receive the wanted amount less the withheld fee, while the offer counts it in full
+    require!(
+        !has_transfer_fee(&context.accounts.token_mint_b)?,
+        ErrorCode::TransferFeeNotSupported
+    );
+
+    // A floor bid on a collection is for one NFT, so it can't be partially filled
+    if options.is_collection_offer {
+        require!(
+            token_b_wanted_amount == 1,
+            ErrorCode::InvalidCollectionOffer
+        );
+    }
+
+    let taker_rent_reimbursement = if options.reimburse_taker_rent {
+        escrow_taker_rent_reimbursement(&context)?
+    } else {
+        0
+    };
+
+    context.accounts.vault.reload()?;
+    let token_a_offered_amount = context.accounts.vault.amount;
+
     context.accounts.offer.set_inner(Offer {
         id,
         maker: context.accounts.maker.key(),
         token_mint_a: context.accounts.token_mint_a.key(),
         token_mint_b: context.accounts.token_mint_b.key(),
+        token_a_offered_amount,
         token_b_wanted_amount,
+        token_a_remaining_amount: token_a_offered_amount,
+        token_b_remaining_amount: token_b_wanted_amount,
+        expires_at: options.expires_at,
+        allowed_taker: options.allowed_taker,
+        is_collection_offer: options.is_collection_offer,
+        taker_rent_reimbursement,
         bump: context.bumps.offer,
     });
+
+    emit!(OfferMade {
+        offer: context.accounts.offer.key(),
+        id,
+        maker: context.accounts.maker.key(),
+        token_mint_a: context.accounts.token_mint_a.key(),
+        token_mint_b: context.accounts.token_mint_b.key(),
+        token_a_offered_amount,
+        token_b_wanted_amount,
+    });
     Ok(())
 }
+
+// Takers pay the rent for maker_token_account_b when the maker doesn't have one yet.
+// The maker can escrow that rent in the offer so whoever creates the account is paid back.
+fn escrow_taker_rent_reimbursement(context: &Context<MakeOffer>) -> Result<u64> {
+    let lamports = Rent::get()?.minimum_balance(associated_token_account_len(
+        &context.accounts.token_mint_b,
+    )?);
+
+    let transfer_accounts_options = Transfer {
+        from: context.accounts.maker.to_account_info(),
+        to: context.accounts.offer.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(
+        context.accounts.system_program.to_account_info(),
+        transfer_accounts_options,
+    );
+
+    transfer(cpi_context, lamports)?;
+    Ok(lamports)
+}
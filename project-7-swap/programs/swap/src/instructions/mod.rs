@@ -4,5 +4,59 @@ pub use make_offer::*;
 pub mod take_offer;
 pub use take_offer::*;
 
+pub mod update_offer;
+pub use update_offer::*;
+
+pub mod refund_offer;
+pub use refund_offer::*;
+
+pub mod close_expired_offer;
+pub use close_expired_offer::*;
+
+pub mod initialize_swap_config;
+pub use initialize_swap_config::*;
+
+pub mod update_swap_config;
+pub use update_swap_config::*;
+
+pub mod create_market;
+pub use create_market::*;
+
+pub mod create_open_orders;
+pub use create_open_orders::*;
+
+pub mod place_order;
+pub use place_order::*;
+
+pub mod take_orders;
+pub use take_orders::*;
+
+pub mod cancel_order;
+pub use cancel_order::*;
+
+pub mod settle_funds;
+pub use settle_funds::*;
+
+pub mod create_pool;
+pub use create_pool::*;
+
+pub mod add_liquidity;
+pub use add_liquidity::*;
+
+pub mod remove_liquidity;
+pub use remove_liquidity::*;
+
+pub mod swap_exact_in;
+pub use swap_exact_in::*;
+
+pub mod make_basket_offer;
+pub use make_basket_offer::*;
+
+pub mod take_basket_offer;
+pub use take_basket_offer::*;
+
+pub mod refund_basket_offer;
+pub use refund_basket_offer::*;
+
 pub mod shared;
 pub use shared::*;
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{error::ErrorCode, Market, OpenOrders, Order, Side};
+
+use super::{transfer_tokens, unlock_order_funds};
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mint::token_program = base_token_program)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = quote_token_program)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"market", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump = market.load()?.bump
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = market,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+
+    // Only needed when this side of the book is full: the open orders account of the worst order,
+    // which is evicted and credited with its locked funds
+    #[account(mut, has_one = market)]
+    pub evicted_open_orders: Option<Account<'info, OpenOrders>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+// Locks what the order could pay: quote tokens for a bid, base tokens for an ask
+pub fn send_order_funds_to_vault(
+    context: &Context<PlaceOrder>,
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> Result<()> {
+    require!(price > 0 && quantity > 0, ErrorCode::InvalidOrder);
+
+    let market = context.accounts.market.load()?;
+    require!(
+        quantity >= market.min_order_quantity,
+        ErrorCode::OrderTooSmall
+    );
+    require!(!market.crosses(side, price), ErrorCode::OrderWouldCross);
+
+    match side {
+        Side::Bid => transfer_tokens(
+            &context.accounts.owner_quote_account,
+            &context.accounts.quote_vault,
+            &market
+                .quote_amount_for(quantity, price)
+                .ok_or(ErrorCode::MathOverflow)?,
+            &context.accounts.quote_mint,
+            &context.accounts.owner,
+            &context.accounts.quote_token_program,
+        ),
+        Side::Ask => transfer_tokens(
+            &context.accounts.owner_base_account,
+            &context.accounts.base_vault,
+            &market
+                .base_amount_for(quantity)
+                .ok_or(ErrorCode::MathOverflow)?,
+            &context.accounts.base_mint,
+            &context.accounts.owner,
+            &context.accounts.base_token_program,
+        ),
+    }
+}
+
+pub fn post_order(
+    mut context: Context<PlaceOrder>,
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> Result<()> {
+    if context.accounts.market.load()?.is_full(side) {
+        evict_worst_order(&mut context, side, price)?;
+    }
+
+    let mut market = context.accounts.market.load_mut()?;
+
+    let order = Order {
+        open_orders: context.accounts.open_orders.key(),
+        order_id: market.next_order_id,
+        price,
+        quantity,
+    };
+    require!(market.insert_order(side, order), ErrorCode::OrderBookFull);
+
+    market.next_order_id += 1;
+    Ok(())
+}
+
+// A full side only takes orders priced better than its worst order, which makes way for them
+fn evict_worst_order(context: &mut Context<PlaceOrder>, side: Side, price: u64) -> Result<()> {
+    let mut market = context.accounts.market.load_mut()?;
+    let evicted_order = market
+        .evict_worst_order(side, price)
+        .ok_or(ErrorCode::OrderBookFull)?;
+
+    // The owner evicting their own order is credited on the account already loaded
+    if evicted_order.open_orders == context.accounts.open_orders.key() {
+        return unlock_order_funds(
+            &mut context.accounts.open_orders,
+            &market,
+            side,
+            &evicted_order,
+        );
+    }
+
+    let evicted_open_orders = context
+        .accounts
+        .evicted_open_orders
+        .as_mut()
+        .ok_or(ErrorCode::InvalidOpenOrders)?;
+    require_keys_eq!(
+        evicted_open_orders.key(),
+        evicted_order.open_orders,
+        ErrorCode::InvalidOpenOrders
+    );
+    unlock_order_funds(evicted_open_orders, &market, side, &evicted_order)
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, BasketOffer};
+
+use super::{
+    close_vault, require_associated_token_account, token_program_for, transfer_tokens_from_vault,
+};
+
+// The remaining accounts are, for each offered token in order:
+// its mint, its vault, and the maker's token account
+#[derive(Accounts)]
+pub struct RefundBasketOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"basket_offer", maker.key().as_ref(), basket_offer.id.to_le_bytes().as_ref()],
+        bump = basket_offer.bump
+    )]
+    pub basket_offer: Account<'info, BasketOffer>,
+
+    pub system_program: Program<'info, System>,
+    #[account(address = anchor_spl::token::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_2022_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Returns every vault to the maker and closes the vaults. The basket offer is closed by Anchor.
+pub fn refund_and_close_vaults<'info>(
+    context: Context<'_, '_, 'info, 'info, RefundBasketOffer<'info>>,
+) -> Result<()> {
+    let basket_offer = &context.accounts.basket_offer;
+    require!(
+        context.remaining_accounts.len() == basket_offer.offered.len() * 3,
+        ErrorCode::InvalidBasketAccounts
+    );
+
+    let seeds = &[
+        b"basket_offer",
+        context.accounts.maker.to_account_info().key.as_ref(),
+        &basket_offer.id.to_le_bytes()[..],
+        &[basket_offer.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    for (leg_accounts, leg) in context
+        .remaining_accounts
+        .chunks(3)
+        .zip(&basket_offer.offered)
+    {
+        let [mint_info, vault_info, maker_token_account_info] = leg_accounts else {
+            return err!(ErrorCode::InvalidBasketAccounts);
+        };
+        require_keys_eq!(mint_info.key(), leg.mint, ErrorCode::InvalidBasketAccounts);
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        let token_program = token_program_for(
+            mint_info,
+            &context.accounts.token_program,
+            &context.accounts.token_2022_program,
+        )?;
+        require_associated_token_account(
+            vault_info,
+            &basket_offer.key(),
+            &leg.mint,
+            token_program.key,
+        )?;
+
+        let vault = InterfaceAccount::<TokenAccount>::try_from(vault_info)?;
+
+        transfer_tokens_from_vault(
+            &vault,
+            &InterfaceAccount::<TokenAccount>::try_from(maker_token_account_info)?,
+            &vault.amount,
+            &mint,
+            &basket_offer.to_account_info(),
+            token_program,
+            &signer_seeds,
+        )?;
+
+        close_vault(
+            &vault,
+            &mint,
+            &context.accounts.maker.to_account_info(),
+            &basket_offer.to_account_info(),
+            token_program,
+            &signer_seeds,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::Offer;
+
+use super::refund_offer_vault_to_maker;
+
+#[derive(Accounts)]
+pub struct RefundOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(mut, mint::token_program = token_program_a)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_a,
+    )]
+    pub maker_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = token_mint_a,
+        seeds = [b"offer", maker.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program_a,
+    )]
+    vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// The maker cancels their offer at any time, getting their tokens and all rent back
+pub fn refund_and_close_vault(context: Context<RefundOffer>) -> Result<()> {
+    refund_offer_vault_to_maker(
+        &context.accounts.offer,
+        &context.accounts.vault,
+        &context.accounts.maker_token_account_a,
+        &context.accounts.maker.to_account_info(),
+        &context.accounts.token_mint_a,
+        &context.accounts.token_program_a,
+    )
+}
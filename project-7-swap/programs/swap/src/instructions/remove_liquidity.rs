@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, Pool};
+
+use super::transfer_tokens_from_vault;
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        has_one = lp_mint,
+        seeds = [b"pool", token_mint_a.key().as_ref(), token_mint_b.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, mint::token_program = lp_token_program)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program_a
+    )]
+    pub provider_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program_b
+    )]
+    pub provider_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = lp_token_program
+    )]
+    pub provider_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Burns `lp_amount` LP tokens for the provider's share of both vaults
+pub fn withdraw_liquidity(
+    context: Context<RemoveLiquidity>,
+    lp_amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidLiquidityAmount);
+
+    let pool = &context.accounts.pool;
+    let (amount_a, amount_b) = pool.withdraw_amounts(
+        lp_amount,
+        context.accounts.vault_a.amount,
+        context.accounts.vault_b.amount,
+    );
+    require!(
+        amount_a >= min_amount_a && amount_b >= min_amount_b,
+        ErrorCode::SlippageExceeded
+    );
+
+    let burn_accounts_options = Burn {
+        mint: context.accounts.lp_mint.to_account_info(),
+        from: context.accounts.provider_lp_token_account.to_account_info(),
+        authority: context.accounts.provider.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(
+        context.accounts.lp_token_program.to_account_info(),
+        burn_accounts_options,
+    );
+
+    burn(cpi_context, lp_amount)?;
+
+    let seeds = &[
+        b"pool",
+        context.accounts.token_mint_a.to_account_info().key.as_ref(),
+        context.accounts.token_mint_b.to_account_info().key.as_ref(),
+        &[pool.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    transfer_tokens_from_vault(
+        &context.accounts.vault_a,
+        &context.accounts.provider_token_account_a,
+        &amount_a,
+        &context.accounts.token_mint_a,
+        &pool.to_account_info(),
+        &context.accounts.token_program_a,
+        &signer_seeds,
+    )?;
+
+    transfer_tokens_from_vault(
+        &context.accounts.vault_b,
+        &context.accounts.provider_token_account_b,
+        &amount_b,
+        &context.accounts.token_mint_b,
+        &pool.to_account_info(),
+        &context.accounts.token_program_b,
+        &signer_seeds,
+    )?;
+
+    context.accounts.pool.lp_supply -= lp_amount;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{Market, OpenOrders};
+
+use super::transfer_tokens_from_vault;
+
+#[derive(Accounts)]
+pub struct SettleFunds<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mint::token_program = base_token_program)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = quote_token_program)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"market", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump = market.load()?.bump
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = market,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = base_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Sends the owner their free base and quote balances from the market vaults
+pub fn withdraw_free_balances(context: Context<SettleFunds>) -> Result<()> {
+    let bump = context.accounts.market.load()?.bump;
+    let base_mint = context.accounts.base_mint.key();
+    let quote_mint = context.accounts.quote_mint.key();
+    let seeds = &[b"market", base_mint.as_ref(), quote_mint.as_ref(), &[bump]];
+    let signer_seeds = [&seeds[..]];
+
+    let open_orders = &mut context.accounts.open_orders;
+
+    if open_orders.base_free > 0 {
+        transfer_tokens_from_vault(
+            &context.accounts.base_vault,
+            &context.accounts.owner_base_account,
+            &open_orders.base_free,
+            &context.accounts.base_mint,
+            &context.accounts.market.to_account_info(),
+            &context.accounts.base_token_program,
+            &signer_seeds,
+        )?;
+        open_orders.base_free = 0;
+    }
+
+    if open_orders.quote_free > 0 {
+        transfer_tokens_from_vault(
+            &context.accounts.quote_vault,
+            &context.accounts.owner_quote_account,
+            &open_orders.quote_free,
+            &context.accounts.quote_mint,
+            &context.accounts.market.to_account_info(),
+            &context.accounts.quote_token_program,
+            &signer_seeds,
+        )?;
+        open_orders.quote_free = 0;
+    }
+
+    Ok(())
+}
@@ -1,8 +1,25 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{
-    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{
+        close_account, harvest_withheld_tokens_to_mint,
+        spl_token_2022::{
+            self,
+            extension::{
+                transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+                StateWithExtensions,
+            },
+            state::{Account as TokenAccountState, Mint as MintState},
+        },
+        transfer_checked, CloseAccount, HarvestWithheldTokensToMint, Mint, TokenAccount,
+        TokenInterface, TransferChecked,
+    },
 };
 
+use crate::{error::ErrorCode, events::OfferCancelled, Offer};
+
+// Works for both the Token Program and the Token Extensions Program.
+// With a Token-2022 transfer-fee mint the fee is withheld from what `to` receives.
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
@@ -22,3 +39,168 @@ pub fn transfer_tokens<'info>(
 
     transfer_checked(cpi_context, *amount, mint.decimals)
 }
+
+pub fn transfer_tokens_from_vault<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: &u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let transfer_accounts_options = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        transfer_accounts_options,
+        signer_seeds,
+    );
+
+    transfer_checked(cpi_context, *amount, mint.decimals)
+}
+
+// Token-2022 won't close an account holding withheld transfer fees, so those are harvested to the mint first
+pub fn close_vault<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if has_transfer_fee(mint)? {
+        let harvest_accounts_options = HarvestWithheldTokensToMint {
+            token_program_id: token_program.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+
+        let cpi_context =
+            CpiContext::new(token_program.to_account_info(), harvest_accounts_options);
+
+        harvest_withheld_tokens_to_mint(cpi_context, vec![vault.to_account_info()])?;
+    }
+
+    let close_accounts_options = CloseAccount {
+        account: vault.to_account_info(),
+        destination: destination.clone(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_accounts_options,
+        signer_seeds,
+    );
+
+    close_account(cpi_context)
+}
+
+// Returns whatever is left in an offer's vault to the maker and closes the vault, for offers ending unfilled.
+// The offer account itself is closed by Anchor.
+pub fn refund_offer_vault_to_maker<'info>(
+    offer: &Account<'info, Offer>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    maker_token_account_a: &InterfaceAccount<'info, TokenAccount>,
+    maker: &AccountInfo<'info>,
+    token_mint_a: &InterfaceAccount<'info, Mint>,
+    token_program_a: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let seeds = &[
+        b"offer",
+        offer.maker.as_ref(),
+        &offer.id.to_le_bytes()[..],
+        &[offer.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    // Read before the transfer, the vault account isn't reloaded afterwards
+    let token_a_refunded_amount = vault.amount;
+
+    transfer_tokens_from_vault(
+        vault,
+        maker_token_account_a,
+        &token_a_refunded_amount,
+        token_mint_a,
+        &offer.to_account_info(),
+        token_program_a,
+        &signer_seeds,
+    )?;
+
+    close_vault(
+        vault,
+        token_mint_a,
+        maker,
+        &offer.to_account_info(),
+        token_program_a,
+        &signer_seeds,
+    )?;
+
+    emit!(OfferCancelled {
+        offer: offer.key(),
+        maker: offer.maker,
+        token_a_refunded_amount,
+    });
+    Ok(())
+}
+
+pub fn has_transfer_fee(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+
+    Ok(mint_state.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+// Size of an associated token account for `mint`. The Associated Token Account program adds
+// the ImmutableOwner extension to Token-2022 accounts, plus whatever the mint's extensions require.
+pub fn associated_token_account_len(mint: &InterfaceAccount<Mint>) -> Result<usize> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(ExtensionType::try_calculate_account_len::<TokenAccountState>(&[])?);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    let mut account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+    if !account_extensions.contains(&ExtensionType::ImmutableOwner) {
+        account_extensions.push(ExtensionType::ImmutableOwner);
+    }
+
+    Ok(ExtensionType::try_calculate_account_len::<TokenAccountState>(&account_extensions)?)
+}
+
+// Basket legs can mix token programs, so each leg uses whichever program owns its mint
+pub fn token_program_for<'a, 'info>(
+    mint: &AccountInfo<'info>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    token_2022_program: &'a Interface<'info, TokenInterface>,
+) -> Result<&'a Interface<'info, TokenInterface>> {
+    if mint.owner == token_program.key {
+        Ok(token_program)
+    } else if mint.owner == token_2022_program.key {
+        Ok(token_2022_program)
+    } else {
+        err!(ErrorCode::InvalidBasketAccounts)
+    }
+}
+
+pub fn require_associated_token_account(
+    account: &AccountInfo,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(
+        account.key(),
+        get_associated_token_address_with_program_id(authority, mint, token_program),
+        ErrorCode::InvalidBasketAccounts
+    );
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, Pool};
+
+use super::{transfer_tokens, transfer_tokens_from_vault};
+
+#[derive(Accounts)]
+pub struct SwapExactIn<'info> {
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        seeds = [b"pool", token_mint_a.key().as_ref(), token_mint_b.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init_if_needed,
+        payer = trader,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = trader,
+        associated_token::token_program = token_program_a
+    )]
+    pub trader_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = trader,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = trader,
+        associated_token::token_program = token_program_b
+    )]
+    pub trader_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Sells exactly `amount_in` of token A for token B when `a_to_b`, otherwise token B for token A,
+// failing if the trader would receive less than `min_amount_out`
+pub fn swap_tokens(
+    context: Context<SwapExactIn>,
+    a_to_b: bool,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(amount_in > 0, ErrorCode::InvalidSwapAmount);
+
+    let accounts = &context.accounts;
+    let (reserve_in, reserve_out) = if a_to_b {
+        (accounts.vault_a.amount, accounts.vault_b.amount)
+    } else {
+        (accounts.vault_b.amount, accounts.vault_a.amount)
+    };
+    require!(
+        reserve_in > 0 && reserve_out > 0,
+        ErrorCode::InsufficientLiquidity
+    );
+
+    let amount_out = accounts.pool.amount_out(amount_in, reserve_in, reserve_out);
+    require!(amount_out > 0, ErrorCode::InsufficientLiquidity);
+    require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+
+    let seeds = &[
+        b"pool",
+        accounts.token_mint_a.to_account_info().key.as_ref(),
+        accounts.token_mint_b.to_account_info().key.as_ref(),
+        &[accounts.pool.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    if a_to_b {
+        transfer_tokens(
+            &accounts.trader_token_account_a,
+            &accounts.vault_a,
+            &amount_in,
+            &accounts.token_mint_a,
+            &accounts.trader,
+            &accounts.token_program_a,
+        )?;
+        transfer_tokens_from_vault(
+            &accounts.vault_b,
+            &accounts.trader_token_account_b,
+            &amount_out,
+            &accounts.token_mint_b,
+            &accounts.pool.to_account_info(),
+            &accounts.token_program_b,
+            &signer_seeds,
+        )
+    } else {
+        transfer_tokens(
+            &accounts.trader_token_account_b,
+            &accounts.vault_b,
+            &amount_in,
+            &accounts.token_mint_b,
+            &accounts.trader,
+            &accounts.token_program_b,
+        )?;
+        transfer_tokens_from_vault(
+            &accounts.vault_a,
+            &accounts.trader_token_account_a,
+            &amount_out,
+            &accounts.token_mint_a,
+            &accounts.pool.to_account_info(),
+            &accounts.token_program_a,
+            &signer_seeds,
+        )
+    }
+}
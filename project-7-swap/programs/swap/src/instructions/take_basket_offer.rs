@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, BasketOffer};
+
+use super::{
+    close_vault, require_associated_token_account, token_program_for, transfer_tokens,
+    transfer_tokens_from_vault,
+};
+
+// The remaining accounts are, for each wanted token in order: its mint, the taker's token account,
+// and the maker's associated token account. Then for each offered token in order: its mint, its vault,
+// and the taker's associated token account. Missing associated token accounts are created, paid by the taker.
+#[derive(Accounts)]
+pub struct TakeBasketOffer<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"basket_offer", maker.key().as_ref(), basket_offer.id.to_le_bytes().as_ref()],
+        bump = basket_offer.bump
+    )]
+    pub basket_offer: Account<'info, BasketOffer>,
+
+    pub system_program: Program<'info, System>,
+    #[account(address = anchor_spl::token::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_2022_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn send_wanted_basket_tokens_to_maker<'info>(
+    context: &Context<'_, '_, 'info, 'info, TakeBasketOffer<'info>>,
+) -> Result<()> {
+    let basket_offer = &context.accounts.basket_offer;
+    require!(
+        context.remaining_accounts.len()
+            == (basket_offer.wanted.len() + basket_offer.offered.len()) * 3,
+        ErrorCode::InvalidBasketAccounts
+    );
+
+    let wanted_accounts = &context.remaining_accounts[..basket_offer.wanted.len() * 3];
+
+    for (leg_accounts, leg) in wanted_accounts.chunks(3).zip(&basket_offer.wanted) {
+        let [mint_info, taker_token_account_info, maker_token_account_info] = leg_accounts else {
+            return err!(ErrorCode::InvalidBasketAccounts);
+        };
+        require_keys_eq!(mint_info.key(), leg.mint, ErrorCode::InvalidBasketAccounts);
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        let token_program = token_program_for(
+            mint_info,
+            &context.accounts.token_program,
+            &context.accounts.token_2022_program,
+        )?;
+        require_associated_token_account(
+            maker_token_account_info,
+            &context.accounts.maker.key(),
+            &leg.mint,
+            token_program.key,
+        )?;
+
+        create_idempotent(CpiContext::new(
+            context.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: context.accounts.taker.to_account_info(),
+                associated_token: maker_token_account_info.clone(),
+                authority: context.accounts.maker.to_account_info(),
+                mint: mint_info.clone(),
+                system_program: context.accounts.system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+
+        transfer_tokens(
+            &InterfaceAccount::<TokenAccount>::try_from(taker_token_account_info)?,
+            &InterfaceAccount::<TokenAccount>::try_from(maker_token_account_info)?,
+            &leg.amount,
+            &mint,
+            &context.accounts.taker,
+            token_program,
+        )?;
+    }
+    Ok(())
+}
+
+// Empties every vault into the taker's accounts and closes the vaults. The basket offer is closed by Anchor.
+pub fn withdraw_and_close_vaults<'info>(
+    context: Context<'_, '_, 'info, 'info, TakeBasketOffer<'info>>,
+) -> Result<()> {
+    let basket_offer = &context.accounts.basket_offer;
+    let seeds = &[
+        b"basket_offer",
+        context.accounts.maker.to_account_info().key.as_ref(),
+        &basket_offer.id.to_le_bytes()[..],
+        &[basket_offer.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    let offered_accounts = &context.remaining_accounts[basket_offer.wanted.len() * 3..];
+
+    for (leg_accounts, leg) in offered_accounts.chunks(3).zip(&basket_offer.offered) {
+        let [mint_info, vault_info, taker_token_account_info] = leg_accounts else {
+            return err!(ErrorCode::InvalidBasketAccounts);
+        };
+        require_keys_eq!(mint_info.key(), leg.mint, ErrorCode::InvalidBasketAccounts);
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        let token_program = token_program_for(
+            mint_info,
+            &context.accounts.token_program,
+            &context.accounts.token_2022_program,
+        )?;
+        require_associated_token_account(
+            vault_info,
+            &basket_offer.key(),
+            &leg.mint,
+            token_program.key,
+        )?;
+
+        create_idempotent(CpiContext::new(
+            context.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: context.accounts.taker.to_account_info(),
+                associated_token: taker_token_account_info.clone(),
+                authority: context.accounts.taker.to_account_info(),
+                mint: mint_info.clone(),
+                system_program: context.accounts.system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+
+        let vault = InterfaceAccount::<TokenAccount>::try_from(vault_info)?;
+
+        transfer_tokens_from_vault(
+            &vault,
+            &InterfaceAccount::<TokenAccount>::try_from(taker_token_account_info)?,
+            &vault.amount,
+            &mint,
+            &basket_offer.to_account_info(),
+            token_program,
+            &signer_seeds,
+        )?;
+
+        close_vault(
+            &vault,
+            &mint,
+            &context.accounts.maker.to_account_info(),
+            &basket_offer.to_account_info(),
+            token_program,
+            &signer_seeds,
+        )?;
+    }
+    Ok(())
+}
@@ -1,15 +1,18 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
     },
+    metadata::{mpl_token_metadata::accounts::Metadata, MetadataAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::Offer;
+use crate::{error::ErrorCode, events::OfferTaken, Offer, SwapConfig};
 
-use super::transfer_tokens;
+use super::{
+    associated_token_account_len, close_vault, has_transfer_fee, transfer_tokens,
+    transfer_tokens_from_vault,
+};
 
 #[derive(Accounts)]
 pub struct TakeOffer<'info> {
@@ -19,16 +22,22 @@ pub struct TakeOffer<'info> {
     #[account(mut)]
     pub maker: SystemAccount<'info>,
 
+    #[account(mut, mint::token_program = token_program_a)]
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
+    #[account(mint::token_program = token_program_b)]
     pub token_mint_b: InterfaceAccount<'info, Mint>,
 
+    // Only needed for collection offers, to check the NFT sent as token B.
+    // Its address is checked in the handler rather than with seeds, so clients can leave it out otherwise.
+    pub token_mint_b_metadata: Option<Box<Account<'info, MetadataAccount>>>,
+
     #[account(
         init_if_needed,
         payer = taker,
         associated_token::mint = token_mint_a,
         associated_token::authority = taker,
-        associated_token::token_program = token_program,
+        associated_token::token_program = token_program_a,
     )]
     pub taker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -36,25 +45,55 @@ pub struct TakeOffer<'info> {
         mut,
         associated_token::mint = token_mint_b,
         associated_token::authority = taker,
-        associated_token::token_program = token_program,
+        associated_token::token_program = token_program_b,
     )]
     pub taker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// CHECK: the maker's associated token account for token B, created in the handler when missing
     #[account(
-        init_if_needed,
-        payer = taker,
-        associated_token::mint = token_mint_b,
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &maker.key(),
+            &token_mint_b.key(),
+            &token_program_b.key()
+        ) @ ErrorCode::InvalidMakerTokenAccountB
+    )]
+    pub maker_token_account_b: UncheckedAccount<'info>,
+
+    // Only needed when the final fill finds tokens in the vault beyond the offer, which go back to the maker
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
         associated_token::authority = maker,
-        associated_token::token_program = token_program,
+        associated_token::token_program = token_program_a,
+    )]
+    pub maker_token_account_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        has_one = fee_recipient,
+        seeds = [b"config"],
+        bump = swap_config.bump
+    )]
+    pub swap_config: Box<Account<'info, SwapConfig>>,
+
+    pub fee_recipient: SystemAccount<'info>,
+
+    // The fee recipient creates its own token accounts, takers never pay rent for them.
+    // Only needed when the take is charged a fee, which collection offers never are.
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program_b,
     )]
-    pub maker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub fee_recipient_token_account_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
-        close = maker,
         has_one = maker,
         has_one = token_mint_a,
-        has_one = token_mint_b,
+        constraint = offer.accepts_token_mint_b(&token_mint_b.key()) @ ErrorCode::InvalidTokenMintB,
+        constraint = offer.can_be_taken_by(&taker.key()) @ ErrorCode::TakerNotAllowed,
         seeds = [b"offer", maker.key().as_ref(), offer.id.to_le_bytes().as_ref()],
         bump = offer.bump
     )]
@@ -64,27 +103,177 @@ pub struct TakeOffer<'info> {
         mut,
         associated_token::mint = token_mint_a,
         associated_token::authority = offer,
-        associated_token::token_program = token_program,
+        associated_token::token_program = token_program_a,
     )]
     vault: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn send_wanted_tokens_to_maker(context: &Context<TakeOffer>) -> Result<()> {
+// The taker pays the rent when the maker has no token B account yet,
+// and is paid back from the offer if the maker escrowed a reimbursement.
+// The repayment is sized from the mint actually delivered, which for collection offers is the NFT's
+// rather than the collection mint the escrow was sized from. Anything left over goes back to the maker.
+pub fn create_maker_token_account_b(context: &mut Context<TakeOffer>) -> Result<()> {
+    if !context.accounts.maker_token_account_b.data_is_empty() {
+        return Ok(());
+    }
+
+    let create_accounts_options = Create {
+        payer: context.accounts.taker.to_account_info(),
+        associated_token: context.accounts.maker_token_account_b.to_account_info(),
+        authority: context.accounts.maker.to_account_info(),
+        mint: context.accounts.token_mint_b.to_account_info(),
+        system_program: context.accounts.system_program.to_account_info(),
+        token_program: context.accounts.token_program_b.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(
+        context.accounts.associated_token_program.to_account_info(),
+        create_accounts_options,
+    );
+
+    create(cpi_context)?;
+
+    let rent = Rent::get()?.minimum_balance(associated_token_account_len(
+        &context.accounts.token_mint_b,
+    )?);
+    let offer = &mut context.accounts.offer;
+    let reimbursement = offer.taker_rent_reimbursement.min(rent);
+    if reimbursement == 0 {
+        return Ok(());
+    }
+
+    offer.sub_lamports(reimbursement)?;
+    context.accounts.taker.add_lamports(reimbursement)?;
+    offer.taker_rent_reimbursement -= reimbursement;
+    Ok(())
+}
+
+// Runs before anything moves. The maker can update the offer at any time, so the taker names the
+// least token A they accept, and a take that lands after a worse update fails instead of filling.
+pub fn validate_take(
+    context: &Context<TakeOffer>,
+    token_b_amount: u64,
+    min_token_a_amount: u64,
+) -> Result<()> {
+    let offer = &context.accounts.offer;
+    require!(
+        !offer.is_expired(Clock::get()?.unix_timestamp),
+        ErrorCode::OfferExpired
+    );
+    require!(
+        token_b_amount > 0 && token_b_amount <= offer.token_b_remaining_amount,
+        ErrorCode::InvalidTakeAmount
+    );
+    let token_a_amount = offer.token_a_amount_for(token_b_amount);
+    require!(token_a_amount > 0, ErrorCode::TakeAmountTooSmall);
+    require!(
+        token_a_amount >= min_token_a_amount,
+        ErrorCode::SlippageExceeded
+    );
+    require!(
+        context.accounts.vault.amount >= token_a_amount,
+        ErrorCode::InsufficientVaultBalance
+    );
+    // Collection offers are taken with an NFT mint the maker never saw, so it's checked on every take
+    require!(
+        !has_transfer_fee(&context.accounts.token_mint_b)?,
+        ErrorCode::TransferFeeNotSupported
+    );
+    if offer.is_collection_offer {
+        require_verified_collection_member(context)?;
+    }
+    Ok(())
+}
+
+pub fn send_wanted_tokens_to_maker(
+    context: &Context<TakeOffer>,
+    token_b_amount: u64,
+) -> Result<()> {
+    // maker_token_account_b may have been created by this instruction, so it isn't a typed account
+    let transfer_accounts_options = TransferChecked {
+        from: context.accounts.taker_token_account_b.to_account_info(),
+        mint: context.accounts.token_mint_b.to_account_info(),
+        to: context.accounts.maker_token_account_b.to_account_info(),
+        authority: context.accounts.taker.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(
+        context.accounts.token_program_b.to_account_info(),
+        transfer_accounts_options,
+    );
+
+    transfer_checked(
+        cpi_context,
+        token_b_amount,
+        context.accounts.token_mint_b.decimals,
+    )?;
+
+    // The protocol fee is paid by the taker on top of what the maker receives
+    let fee = take_fee(context, token_b_amount);
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let fee_recipient_token_account_b = context
+        .accounts
+        .fee_recipient_token_account_b
+        .as_ref()
+        .ok_or(ErrorCode::MissingFeeRecipientTokenAccount)?;
     transfer_tokens(
         &context.accounts.taker_token_account_b,
-        &context.accounts.maker_token_account_b,
-        &context.accounts.offer.token_b_wanted_amount,
+        fee_recipient_token_account_b,
+        &fee,
         &context.accounts.token_mint_b,
         &context.accounts.taker,
-        &context.accounts.token_program,
+        &context.accounts.token_program_b,
     )
 }
 
-pub fn withdraw_and_close_vault(context: Context<TakeOffer>) -> Result<()> {
+// Collection offers trade a single NFT, which has no fraction to charge a fee on
+fn take_fee(context: &Context<TakeOffer>, token_b_amount: u64) -> u64 {
+    if context.accounts.offer.is_collection_offer {
+        return 0;
+    }
+
+    context.accounts.swap_config.fee_for(token_b_amount)
+}
+
+// Like claim_prize in the token lottery: the NFT's metadata must list the offer's collection as verified
+fn require_verified_collection_member(context: &Context<TakeOffer>) -> Result<()> {
+    let token_mint_b = &context.accounts.token_mint_b;
+    require!(
+        token_mint_b.decimals == 0 && token_mint_b.supply == 1,
+        ErrorCode::NotAnNft
+    );
+
+    let metadata = context
+        .accounts
+        .token_mint_b_metadata
+        .as_ref()
+        .ok_or(ErrorCode::MissingNftMetadata)?;
+    require_keys_eq!(
+        metadata.key(),
+        Metadata::find_pda(&token_mint_b.key()).0,
+        ErrorCode::MissingNftMetadata
+    );
+
+    let is_verified_member = metadata.collection.as_ref().is_some_and(|collection| {
+        collection.verified && collection.key == context.accounts.offer.token_mint_b
+    });
+    require!(is_verified_member, ErrorCode::NftNotInCollection);
+    Ok(())
+}
+
+// Sends the taker their share of the vault, and closes the vault and the offer once it is fully filled
+pub fn withdraw_and_close_vault(context: Context<TakeOffer>, token_b_amount: u64) -> Result<()> {
+    let token_a_amount = context.accounts.offer.token_a_amount_for(token_b_amount);
+    let fee = take_fee(&context, token_b_amount);
+
     let seeds = &[
         b"offer",
         context.accounts.maker.to_account_info().key.as_ref(),
@@ -93,36 +282,66 @@ pub fn withdraw_and_close_vault(context: Context<TakeOffer>) -> Result<()> {
     ];
     let signer_seeds = [&seeds[..]];
 
-    let accounts = TransferChecked {
-        from: context.accounts.vault.to_account_info(),
-        to: context.accounts.taker_token_account_a.to_account_info(),
-        mint: context.accounts.token_mint_a.to_account_info(),
-        authority: context.accounts.offer.to_account_info(),
-    };
-
-    let cpi_context = CpiContext::new_with_signer(
-        context.accounts.token_program.to_account_info(),
-        accounts,
+    transfer_tokens_from_vault(
+        &context.accounts.vault,
+        &context.accounts.taker_token_account_a,
+        &token_a_amount,
+        &context.accounts.token_mint_a,
+        &context.accounts.offer.to_account_info(),
+        &context.accounts.token_program_a,
         &signer_seeds,
-    );
-
-    transfer_checked(
-        cpi_context,
-        context.accounts.vault.amount,
-        context.accounts.token_mint_a.decimals,
     )?;
 
-    let accounts = CloseAccount {
-        account: context.accounts.vault.to_account_info(),
-        destination: context.accounts.taker.to_account_info(),
-        authority: context.accounts.offer.to_account_info(),
-    };
+    let offer = &mut context.accounts.offer;
+    offer.token_a_remaining_amount -= token_a_amount;
+    offer.token_b_remaining_amount -= token_b_amount;
+
+    emit!(OfferTaken {
+        offer: offer.key(),
+        maker: offer.maker,
+        taker: context.accounts.taker.key(),
+        token_a_amount,
+        token_b_amount,
+        fee,
+        is_filled: offer.is_filled(),
+    });
 
-    let cpi_context = CpiContext::new_with_signer(
-        context.accounts.token_program.to_account_info(),
-        accounts,
+    if !offer.is_filled() {
+        return Ok(());
+    }
+
+    // Anyone can send tokens to the vault, and a token account holding any can't be closed
+    context.accounts.vault.reload()?;
+    let token_a_left_over_amount = context.accounts.vault.amount;
+    if token_a_left_over_amount > 0 {
+        let maker_token_account_a = context
+            .accounts
+            .maker_token_account_a
+            .as_ref()
+            .ok_or(ErrorCode::MissingMakerTokenAccountA)?;
+        transfer_tokens_from_vault(
+            &context.accounts.vault,
+            maker_token_account_a,
+            &token_a_left_over_amount,
+            &context.accounts.token_mint_a,
+            &context.accounts.offer.to_account_info(),
+            &context.accounts.token_program_a,
+            &signer_seeds,
+        )?;
+    }
+
+    // The maker paid for the vault, so its rent goes back to them
+    close_vault(
+        &context.accounts.vault,
+        &context.accounts.token_mint_a,
+        &context.accounts.maker.to_account_info(),
+        &context.accounts.offer.to_account_info(),
+        &context.accounts.token_program_a,
         &signer_seeds,
-    );
+    )?;
 
-    close_account(cpi_context)
+    context
+        .accounts
+        .offer
+        .close(context.accounts.maker.to_account_info())
 }
@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{error::ErrorCode, Market, OpenOrders, Side};
+
+use super::transfer_tokens;
+
+// The remaining accounts are the open orders accounts of the makers being filled, in book order
+#[derive(Accounts)]
+pub struct TakeOrders<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mint::token_program = base_token_program)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = quote_token_program)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"market", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump = market.load()?.bump
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = market,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+// Fills resting orders on the other side of the book, best price first, until `quantity` lots are filled,
+// the next order is beyond `limit_price`, or the remaining accounts run out. Each fill is priced at the
+// resting order's price. The taker pays into the vault now, and both sides are credited to their open orders.
+pub fn match_orders<'info>(
+    context: Context<'_, '_, 'info, 'info, TakeOrders<'info>>,
+    side: Side,
+    quantity: u64,
+    limit_price: u64,
+) -> Result<()> {
+    require!(quantity > 0, ErrorCode::InvalidOrder);
+
+    let mut market = context.accounts.market.load_mut()?;
+    let taker_open_orders = &mut context.accounts.open_orders;
+    let mut maker_open_orders_accounts = context.remaining_accounts.iter();
+
+    let mut base_lots_filled: u64 = 0;
+    let mut quote_amount_filled: u64 = 0;
+
+    while base_lots_filled < quantity {
+        let Some(order) = market.best_order(side.opposite()) else {
+            break;
+        };
+        let within_limit = match side {
+            Side::Bid => order.price <= limit_price,
+            Side::Ask => order.price >= limit_price,
+        };
+        if !within_limit {
+            break;
+        }
+
+        let fill_quantity = order.quantity.min(quantity - base_lots_filled);
+        let base_amount = market
+            .base_amount_for(fill_quantity)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let quote_amount = market
+            .quote_amount_for(fill_quantity, order.price)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // A taker filling their own order is credited on the account already loaded
+        if order.open_orders == taker_open_orders.key() {
+            credit_maker(taker_open_orders, side, base_amount, quote_amount)?;
+        } else {
+            let Some(maker_open_orders_info) = maker_open_orders_accounts.next() else {
+                break;
+            };
+            require_keys_eq!(
+                maker_open_orders_info.key(),
+                order.open_orders,
+                ErrorCode::InvalidOpenOrders
+            );
+            let mut maker_open_orders = Account::<OpenOrders>::try_from(maker_open_orders_info)?;
+            credit_maker(&mut maker_open_orders, side, base_amount, quote_amount)?;
+            maker_open_orders.exit(&crate::ID)?;
+        }
+
+        market.fill_best_order(side.opposite(), fill_quantity);
+        base_lots_filled += fill_quantity;
+        quote_amount_filled = quote_amount_filled
+            .checked_add(quote_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    require!(base_lots_filled > 0, ErrorCode::NoOrdersFilled);
+
+    let base_amount_filled = market
+        .base_amount_for(base_lots_filled)
+        .ok_or(ErrorCode::MathOverflow)?;
+    drop(market);
+
+    match side {
+        Side::Bid => {
+            taker_open_orders.base_free = taker_open_orders
+                .base_free
+                .checked_add(base_amount_filled)
+                .ok_or(ErrorCode::MathOverflow)?;
+            transfer_tokens(
+                &context.accounts.owner_quote_account,
+                &context.accounts.quote_vault,
+                &quote_amount_filled,
+                &context.accounts.quote_mint,
+                &context.accounts.owner,
+                &context.accounts.quote_token_program,
+            )
+        }
+        Side::Ask => {
+            taker_open_orders.quote_free = taker_open_orders
+                .quote_free
+                .checked_add(quote_amount_filled)
+                .ok_or(ErrorCode::MathOverflow)?;
+            transfer_tokens(
+                &context.accounts.owner_base_account,
+                &context.accounts.base_vault,
+                &base_amount_filled,
+                &context.accounts.base_mint,
+                &context.accounts.owner,
+                &context.accounts.base_token_program,
+            )
+        }
+    }
+}
+
+// A taker buying fills asks, whose makers receive quote tokens, and a taker selling fills bids,
+// whose makers receive base tokens
+fn credit_maker(
+    maker_open_orders: &mut OpenOrders,
+    taker_side: Side,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Result<()> {
+    match taker_side {
+        Side::Bid => {
+            maker_open_orders.quote_free = maker_open_orders
+                .quote_free
+                .checked_add(quote_amount)
+                .ok_or(ErrorCode::MathOverflow)?
+        }
+        Side::Ask => {
+            maker_open_orders.base_free = maker_open_orders
+                .base_free
+                .checked_add(base_amount)
+                .ok_or(ErrorCode::MathOverflow)?
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, events::OfferUpdated, Offer};
+
+use super::{transfer_tokens, transfer_tokens_from_vault};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OfferChanges {
+    // None keeps the current remaining wanted amount
+    pub token_b_wanted_amount: Option<u64>,
+    pub token_a_deposit_amount: u64,
+    pub token_a_withdraw_amount: u64,
+    // None keeps the current expiry
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_a,
+    )]
+    pub maker_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = token_mint_a,
+        seeds = [b"offer", maker.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program_a,
+    )]
+    vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Tops up the vault from the maker's account, or sends part of it back to the maker
+pub fn move_offered_tokens(context: &Context<UpdateOffer>, changes: &OfferChanges) -> Result<()> {
+    if changes.token_a_deposit_amount > 0 {
+        transfer_tokens(
+            &context.accounts.maker_token_account_a,
+            &context.accounts.vault,
+            &changes.token_a_deposit_amount,
+            &context.accounts.token_mint_a,
+            &context.accounts.maker,
+            &context.accounts.token_program_a,
+        )?;
+    }
+
+    if changes.token_a_withdraw_amount > 0 {
+        let seeds = &[
+            b"offer",
+            context.accounts.maker.to_account_info().key.as_ref(),
+            &context.accounts.offer.id.to_le_bytes()[..],
+            &[context.accounts.offer.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        transfer_tokens_from_vault(
+            &context.accounts.vault,
+            &context.accounts.maker_token_account_a,
+            &changes.token_a_withdraw_amount,
+            &context.accounts.token_mint_a,
+            &context.accounts.offer.to_account_info(),
+            &context.accounts.token_program_a,
+            &signer_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+// The amended offer is for whatever the vault now holds, so fills made before the update
+// no longer count towards the offered and wanted amounts
+pub fn save_offer_changes(context: Context<UpdateOffer>, changes: OfferChanges) -> Result<()> {
+    if let Some(expires_at) = changes.expires_at {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidExpiry
+        );
+        // Offers without an expiry never expire, so there is nothing to extend
+        require!(
+            context
+                .accounts
+                .offer
+                .expires_at
+                .is_some_and(|current_expires_at| expires_at > current_expires_at),
+            ErrorCode::ExpiryNotExtended
+        );
+    }
+
+    let offer = &context.accounts.offer;
+    let token_b_wanted_amount = changes
+        .token_b_wanted_amount
+        .unwrap_or(offer.token_b_remaining_amount);
+    if offer.is_collection_offer {
+        require!(
+            token_b_wanted_amount == 1,
+            ErrorCode::InvalidCollectionOffer
+        );
+    }
+
+    // Withdrawing everything is a refund, which also closes the offer
+    context.accounts.vault.reload()?;
+    let token_a_offered_amount = context.accounts.vault.amount;
+    require!(
+        token_a_offered_amount > 0 && token_b_wanted_amount > 0,
+        ErrorCode::InvalidOfferAmount
+    );
+
+    let offer = &mut context.accounts.offer;
+    offer.token_a_offered_amount = token_a_offered_amount;
+    offer.token_b_wanted_amount = token_b_wanted_amount;
+    offer.token_a_remaining_amount = token_a_offered_amount;
+    offer.token_b_remaining_amount = token_b_wanted_amount;
+    if changes.expires_at.is_some() {
+        offer.expires_at = changes.expires_at;
+    }
+
+    emit!(OfferUpdated {
+        offer: offer.key(),
+        maker: offer.maker,
+        token_a_offered_amount,
+        token_b_wanted_amount,
+        expires_at: offer.expires_at,
+    });
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, SwapConfig, MAX_FEE_BASIS_POINTS};
+
+#[derive(Accounts)]
+pub struct UpdateSwapConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"config"],
+        bump = swap_config.bump
+    )]
+    pub swap_config: Account<'info, SwapConfig>,
+}
+
+pub fn update_fee(
+    context: Context<UpdateSwapConfig>,
+    fee_basis_points: u16,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    require!(
+        fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        ErrorCode::FeeTooHigh
+    );
+
+    let swap_config = &mut context.accounts.swap_config;
+    swap_config.fee_basis_points = fee_basis_points;
+    swap_config.fee_recipient = fee_recipient;
+    Ok(())
+}
+
+pub fn update_admin(context: Context<UpdateSwapConfig>, new_admin: Pubkey) -> Result<()> {
+    context.accounts.swap_config.admin = new_admin;
+    Ok(())
+}
@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -15,18 +16,170 @@ declare_id!("DAehvmx2vZoWCJi7Qo3Y4YF5vrEWHQRJ288kqKwDy5DV");
 pub mod swap {
     use super::*;
 
+    pub fn initialize_swap_config(
+        context: Context<InitializeSwapConfig>,
+        fee_basis_points: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_swap_config::save_swap_config(
+            context,
+            fee_basis_points,
+            fee_recipient,
+        )
+    }
+
+    pub fn update_swap_config(
+        context: Context<UpdateSwapConfig>,
+        fee_basis_points: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::update_swap_config::update_fee(context, fee_basis_points, fee_recipient)
+    }
+
+    pub fn set_swap_admin(context: Context<UpdateSwapConfig>, new_admin: Pubkey) -> Result<()> {
+        instructions::update_swap_config::update_admin(context, new_admin)
+    }
+
     pub fn make_offer(
         context: Context<MakeOffer>,
         id: u64,
         token_a_offered_amount: u64,
         token_b_wanted_amount: u64,
+        options: OfferOptions,
     ) -> Result<()> {
+        instructions::make_offer::validate_offer(token_a_offered_amount, token_b_wanted_amount)?;
         instructions::make_offer::send_offered_tokens_to_vault(&context, token_a_offered_amount)?;
-        instructions::make_offer::save_offer(context, id, token_b_wanted_amount)
+        instructions::make_offer::save_offer(context, id, token_b_wanted_amount, options)
+    }
+
+    pub fn take_offer(
+        mut context: Context<TakeOffer>,
+        token_b_amount: u64,
+        min_token_a_amount: u64,
+    ) -> Result<()> {
+        instructions::take_offer::validate_take(&context, token_b_amount, min_token_a_amount)?;
+        instructions::take_offer::create_maker_token_account_b(&mut context)?;
+        instructions::take_offer::send_wanted_tokens_to_maker(&context, token_b_amount)?;
+        instructions::take_offer::withdraw_and_close_vault(context, token_b_amount)
+    }
+
+    pub fn update_offer(context: Context<UpdateOffer>, changes: OfferChanges) -> Result<()> {
+        instructions::update_offer::move_offered_tokens(&context, &changes)?;
+        instructions::update_offer::save_offer_changes(context, changes)
     }
 
-    pub fn take_offer(context: Context<TakeOffer>) -> Result<()> {
-        instructions::take_offer::send_wanted_tokens_to_maker(&context)?;
-        instructions::take_offer::withdraw_and_close_vault(context)
+    pub fn refund_offer(context: Context<RefundOffer>) -> Result<()> {
+        instructions::refund_offer::refund_and_close_vault(context)
+    }
+
+    pub fn close_expired_offer(context: Context<CloseExpiredOffer>) -> Result<()> {
+        instructions::close_expired_offer::refund_expired_offer_and_close_vault(context)
+    }
+
+    pub fn create_market(
+        context: Context<CreateMarket>,
+        base_lot_size: u64,
+        min_order_quantity: u64,
+    ) -> Result<()> {
+        instructions::create_market::save_market(context, base_lot_size, min_order_quantity)
+    }
+
+    pub fn create_open_orders(context: Context<CreateOpenOrders>) -> Result<()> {
+        instructions::create_open_orders::save_open_orders(context)
+    }
+
+    pub fn place_order(
+        context: Context<PlaceOrder>,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        instructions::place_order::send_order_funds_to_vault(&context, side, price, quantity)?;
+        instructions::place_order::post_order(context, side, price, quantity)
+    }
+
+    pub fn take_orders<'info>(
+        context: Context<'_, '_, 'info, 'info, TakeOrders<'info>>,
+        side: Side,
+        quantity: u64,
+        limit_price: u64,
+    ) -> Result<()> {
+        instructions::take_orders::match_orders(context, side, quantity, limit_price)
+    }
+
+    pub fn cancel_order(context: Context<CancelOrder>, side: Side, order_id: u64) -> Result<()> {
+        instructions::cancel_order::remove_order_and_unlock_funds(context, side, order_id)
+    }
+
+    pub fn settle_funds(context: Context<SettleFunds>) -> Result<()> {
+        instructions::settle_funds::withdraw_free_balances(context)
+    }
+
+    pub fn create_pool(context: Context<CreatePool>, fee_basis_points: u16) -> Result<()> {
+        instructions::create_pool::save_pool(context, fee_basis_points)
+    }
+
+    pub fn add_liquidity(
+        context: Context<AddLiquidity>,
+        max_amount_a: u64,
+        max_amount_b: u64,
+        min_lp_amount: u64,
+    ) -> Result<()> {
+        instructions::add_liquidity::deposit_liquidity(
+            context,
+            max_amount_a,
+            max_amount_b,
+            min_lp_amount,
+        )
+    }
+
+    pub fn remove_liquidity(
+        context: Context<RemoveLiquidity>,
+        lp_amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        instructions::remove_liquidity::withdraw_liquidity(
+            context,
+            lp_amount,
+            min_amount_a,
+            min_amount_b,
+        )
+    }
+
+    pub fn swap_exact_in(
+        context: Context<SwapExactIn>,
+        a_to_b: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::swap_exact_in::swap_tokens(context, a_to_b, amount_in, min_amount_out)
+    }
+
+    pub fn make_basket_offer<'info>(
+        context: Context<'_, '_, 'info, 'info, MakeBasketOffer<'info>>,
+        id: u64,
+        offered_amounts: Vec<u64>,
+        wanted: Vec<BasketLeg>,
+    ) -> Result<()> {
+        instructions::make_basket_offer::send_offered_tokens_to_vaults(
+            context,
+            id,
+            offered_amounts,
+            wanted,
+        )
+    }
+
+    pub fn take_basket_offer<'info>(
+        context: Context<'_, '_, 'info, 'info, TakeBasketOffer<'info>>,
+    ) -> Result<()> {
+        instructions::take_basket_offer::send_wanted_basket_tokens_to_maker(&context)?;
+        instructions::take_basket_offer::withdraw_and_close_vaults(context)
+    }
+
+    pub fn refund_basket_offer<'info>(
+        context: Context<'_, '_, 'info, 'info, RefundBasketOffer<'info>>,
+    ) -> Result<()> {
+        instructions::refund_basket_offer::refund_and_close_vaults(context)
     }
 }
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_BASKET_LEGS;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+// An all-or-nothing trade of several tokens for several tokens.
+// Each offered token sits in its own vault, the basket offer's associated token account for that mint.
+#[account]
+#[derive(InitSpace)]
+pub struct BasketOffer {
+    pub id: u64,
+    pub maker: Pubkey,
+    #[max_len(MAX_BASKET_LEGS)]
+    pub offered: Vec<BasketLeg>,
+    #[max_len(MAX_BASKET_LEGS)]
+    pub wanted: Vec<BasketLeg>,
+    pub bump: u8,
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_ORDERS_PER_SIDE;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        }
+    }
+}
+
+// A resting limit order. Quantity is in base lots, price is in quote tokens per base lot.
+#[zero_copy]
+#[derive(Default)]
+pub struct Order {
+    pub open_orders: Pubkey,
+    pub order_id: u64,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+// Order book for one base/quote pair. Bids are kept sorted best (highest) price first,
+// asks best (lowest) price first, and orders at the same price in the order they were posted.
+#[account(zero_copy)]
+pub struct Market {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_lot_size: u64,
+    pub min_order_quantity: u64,
+    pub next_order_id: u64,
+    pub bid_count: u64,
+    pub ask_count: u64,
+    pub bids: [Order; MAX_ORDERS_PER_SIDE],
+    pub asks: [Order; MAX_ORDERS_PER_SIDE],
+    pub bump: u8,
+    pub padding: [u8; 7],
+}
+
+impl Market {
+    pub fn base_amount_for(&self, quantity: u64) -> Option<u64> {
+        quantity.checked_mul(self.base_lot_size)
+    }
+
+    pub fn quote_amount_for(&self, quantity: u64, price: u64) -> Option<u64> {
+        quantity.checked_mul(price)
+    }
+
+    pub fn orders(&self, side: Side) -> &[Order] {
+        match side {
+            Side::Bid => &self.bids[..self.bid_count as usize],
+            Side::Ask => &self.asks[..self.ask_count as usize],
+        }
+    }
+
+    pub fn best_order(&self, side: Side) -> Option<Order> {
+        self.orders(side).first().copied()
+    }
+
+    // Whether an order at `price` on `side` would match the best order on the other side
+    pub fn crosses(&self, side: Side, price: u64) -> bool {
+        match (side, self.best_order(side.opposite())) {
+            (Side::Bid, Some(best_ask)) => price >= best_ask.price,
+            (Side::Ask, Some(best_bid)) => price <= best_bid.price,
+            (_, None) => false,
+        }
+    }
+
+    pub fn is_full(&self, side: Side) -> bool {
+        self.orders(side).len() == MAX_ORDERS_PER_SIDE
+    }
+
+    // Removes the worst order on `side` to make room for an order at a better `price`.
+    // Returns None when `price` doesn't beat it, so a full book can't be churned at the same price.
+    pub fn evict_worst_order(&mut self, side: Side, price: u64) -> Option<Order> {
+        let worst_order = *self.orders(side).last()?;
+        let beats_worst_order = match side {
+            Side::Bid => price > worst_order.price,
+            Side::Ask => price < worst_order.price,
+        };
+        if !beats_worst_order {
+            return None;
+        }
+
+        let index = self.orders(side).len() - 1;
+        Some(self.remove_order(side, index))
+    }
+
+    // Returns false when that side of the book is full
+    pub fn insert_order(&mut self, side: Side, order: Order) -> bool {
+        let (orders, count) = self.side_mut(side);
+        let len = *count as usize;
+        if len == MAX_ORDERS_PER_SIDE {
+            return false;
+        }
+
+        let index = orders[..len]
+            .iter()
+            .position(|resting| match side {
+                Side::Bid => order.price > resting.price,
+                Side::Ask => order.price < resting.price,
+            })
+            .unwrap_or(len);
+
+        orders.copy_within(index..len, index + 1);
+        orders[index] = order;
+        *count += 1;
+        true
+    }
+
+    pub fn remove_order(&mut self, side: Side, index: usize) -> Order {
+        let (orders, count) = self.side_mut(side);
+        let len = *count as usize;
+        let order = orders[index];
+
+        orders.copy_within(index + 1..len, index);
+        orders[len - 1] = Order::default();
+        *count -= 1;
+        order
+    }
+
+    // Takes `quantity` lots from the best order on `side`, removing it once empty
+    pub fn fill_best_order(&mut self, side: Side, quantity: u64) {
+        let (orders, _) = self.side_mut(side);
+        orders[0].quantity -= quantity;
+        if orders[0].quantity == 0 {
+            self.remove_order(side, 0);
+        }
+    }
+
+    pub fn find_order(&self, side: Side, order_id: u64, open_orders: &Pubkey) -> Option<usize> {
+        self.orders(side)
+            .iter()
+            .position(|order| order.order_id == order_id && order.open_orders == *open_orders)
+    }
+
+    fn side_mut(&mut self, side: Side) -> (&mut [Order; MAX_ORDERS_PER_SIDE], &mut u64) {
+        match side {
+            Side::Bid => (&mut self.bids, &mut self.bid_count),
+            Side::Ask => (&mut self.asks, &mut self.ask_count),
+        }
+    }
+}
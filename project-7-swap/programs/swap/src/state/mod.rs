@@ -1,3 +1,13 @@
+pub mod basket_offer;
+pub mod market;
 pub mod offer;
+pub mod open_orders;
+pub mod pool;
+pub mod swap_config;
 
+pub use basket_offer::*;
+pub use market::*;
 pub use offer::*;
+pub use open_orders::*;
+pub use pool::*;
+pub use swap_config::*;
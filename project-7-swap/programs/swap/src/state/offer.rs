@@ -7,6 +7,46 @@ pub struct Offer {
     pub maker: Pubkey,
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
+    pub token_a_offered_amount: u64,
     pub token_b_wanted_amount: u64,
+    pub token_a_remaining_amount: u64,
+    pub token_b_remaining_amount: u64,
+    pub expires_at: Option<i64>,
+    pub allowed_taker: Option<Pubkey>,
+    // When set, token_mint_b is a Metaplex collection mint, and any NFT verified as part of
+    // that collection fills the offer
+    pub is_collection_offer: bool,
+    // Lamports the maker escrowed in the offer to repay the taker who creates maker_token_account_b
+    pub taker_rent_reimbursement: u64,
     pub bump: u8,
 }
+
+impl Offer {
+    // Token A owed for a fill of `token_b_amount`, at the offer's remaining ratio.
+    // Rounds down, in the maker's favor. Filling the whole remainder returns the whole remainder.
+    pub fn token_a_amount_for(&self, token_b_amount: u64) -> u64 {
+        (token_b_amount as u128 * self.token_a_remaining_amount as u128
+            / self.token_b_remaining_amount as u128) as u64
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.token_b_remaining_amount == 0
+    }
+
+    // Offers without an expiry never expire
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn accepts_token_mint_b(&self, token_mint_b: &Pubkey) -> bool {
+        self.is_collection_offer || self.token_mint_b == *token_mint_b
+    }
+
+    // Private offers can only be taken by the designated taker, public offers by anyone
+    pub fn can_be_taken_by(&self, taker: &Pubkey) -> bool {
+        match self.allowed_taker {
+            Some(allowed_taker) => allowed_taker == *taker,
+            None => true,
+        }
+    }
+}
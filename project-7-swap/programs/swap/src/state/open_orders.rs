@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+// A user's balances on one market. Fills and cancellations credit the free balances,
+// which the owner withdraws with settle_funds.
+#[account]
+#[derive(InitSpace)]
+pub struct OpenOrders {
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::{BASIS_POINTS, MINIMUM_LIQUIDITY};
+
+// Constant product pool. Reserves are the vault balances, and `lp_supply` includes
+// MINIMUM_LIQUIDITY that is never minted, so the pool can't be drained back to empty.
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee_basis_points: u16,
+    pub lp_supply: u64,
+    pub bump: u8,
+    pub lp_mint_bump: u8,
+}
+
+impl Pool {
+    // LP tokens to mint and the token A and B to take for a deposit of at most `max_amount_a`
+    // and `max_amount_b`. After the first deposit, amounts are taken at the pool's ratio,
+    // rounded up in the pool's favor.
+    pub fn deposit_amounts(
+        &self,
+        reserve_a: u64,
+        reserve_b: u64,
+        max_amount_a: u64,
+        max_amount_b: u64,
+    ) -> Option<(u64, u64, u64)> {
+        if self.lp_supply == 0 {
+            let liquidity = integer_sqrt(max_amount_a as u128 * max_amount_b as u128) as u64;
+            return Some((
+                liquidity.checked_sub(MINIMUM_LIQUIDITY)?,
+                max_amount_a,
+                max_amount_b,
+            ));
+        }
+
+        let lp_supply = self.lp_supply as u128;
+        let lp_amount = (max_amount_a as u128 * lp_supply / reserve_a as u128)
+            .min(max_amount_b as u128 * lp_supply / reserve_b as u128);
+        let amount_a = (lp_amount * reserve_a as u128).div_ceil(lp_supply);
+        let amount_b = (lp_amount * reserve_b as u128).div_ceil(lp_supply);
+
+        Some((
+            lp_amount.try_into().ok()?,
+            amount_a.try_into().ok()?,
+            amount_b.try_into().ok()?,
+        ))
+    }
+
+    // Token A and B returned for burning `lp_amount`, rounded down in the pool's favor
+    pub fn withdraw_amounts(&self, lp_amount: u64, reserve_a: u64, reserve_b: u64) -> (u64, u64) {
+        let lp_supply = self.lp_supply as u128;
+        (
+            (lp_amount as u128 * reserve_a as u128 / lp_supply) as u64,
+            (lp_amount as u128 * reserve_b as u128 / lp_supply) as u64,
+        )
+    }
+
+    // Output for `amount_in` after the pool fee, keeping reserve_in * reserve_out from decreasing
+    pub fn amount_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> u64 {
+        let amount_in_after_fee = amount_in as u128
+            * (BASIS_POINTS - self.fee_basis_points) as u128
+            / BASIS_POINTS as u128;
+        (reserve_out as u128 * amount_in_after_fee / (reserve_in as u128 + amount_in_after_fee))
+            as u64
+    }
+}
+
+// Newton's method, rounds down
+fn integer_sqrt(value: u128) -> u128 {
+    let mut root = value;
+    let mut next = root.div_ceil(2);
+    while next < root {
+        root = next;
+        next = (root + value / root) / 2;
+    }
+    root
+}
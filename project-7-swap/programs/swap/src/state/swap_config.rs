@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::BASIS_POINTS;
+
+#[account]
+#[derive(InitSpace)]
+pub struct SwapConfig {
+    pub admin: Pubkey,
+    pub fee_basis_points: u16,
+    pub fee_recipient: Pubkey,
+    pub bump: u8,
+}
+
+impl SwapConfig {
+    // Fee the taker pays on top of the token B they send to the maker, rounded down
+    pub fn fee_for(&self, token_b_amount: u64) -> u64 {
+        (token_b_amount as u128 * self.fee_basis_points as u128 / BASIS_POINTS as u128) as u64
+    }
+}
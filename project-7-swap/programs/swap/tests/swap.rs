@@ -0,0 +1,814 @@
+// Runs against the compiled program in target/deploy, so build it before running the tests:
+//   cargo build-sbf    (or anchor build)
+//   cargo test -p swap
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::Instruction,
+        program_pack::Pack,
+        system_instruction::{self, SystemError},
+        system_program,
+    },
+    AccountSerialize, AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id, spl_associated_token_account,
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::instruction as transfer_fee_instruction, ExtensionType,
+            StateWithExtensions,
+        },
+        state::{Account as TokenAccountState, Mint as MintState},
+    },
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use swap::{
+    error::ErrorCode,
+    events::{OfferCancelled, OfferMade, OfferTaken},
+    OfferChanges, OfferOptions, SwapConfig,
+};
+
+const FEE_BASIS_POINTS: u16 = 30;
+const TOKEN_A_OFFERED_AMOUNT: u64 = 1_000_000;
+const TOKEN_B_WANTED_AMOUNT: u64 = 500_000;
+const TAKER_TOKEN_B_AMOUNT: u64 = 2_000_000;
+
+struct Setup {
+    context: ProgramTestContext,
+    maker: Keypair,
+    taker: Keypair,
+    fee_recipient: Pubkey,
+    fee_recipient_token_account_b: Pubkey,
+    swap_config: Pubkey,
+    token_mint_a: Pubkey,
+    token_mint_b: Pubkey,
+    token_program_a: Pubkey,
+    token_program_b: Pubkey,
+    maker_token_account_a: Pubkey,
+    taker_token_account_b: Pubkey,
+}
+
+struct OfferAccounts {
+    offer: Pubkey,
+    vault: Pubkey,
+}
+
+// Starts a test validator with the swap program and a swap config, then gives the maker
+// token A and the taker token B
+async fn setup(token_program_a: Pubkey, token_program_b: Pubkey) -> Setup {
+    std::env::set_var("SBF_OUT_DIR", "../../target/deploy");
+    let mut program_test = ProgramTest::new("swap", swap::ID, None);
+
+    let maker = Keypair::new();
+    let taker = Keypair::new();
+    for user in [&maker, &taker] {
+        program_test.add_account(
+            user.pubkey(),
+            Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+    }
+
+    // The config can only be created by the upgrade authority, so it is written directly
+    let fee_recipient = Pubkey::new_unique();
+    let (swap_config, bump) = Pubkey::find_program_address(&[b"config"], &swap::ID);
+    let mut swap_config_data = vec![];
+    SwapConfig {
+        admin: fee_recipient,
+        fee_basis_points: FEE_BASIS_POINTS,
+        fee_recipient,
+        bump,
+    }
+    .try_serialize(&mut swap_config_data)
+    .unwrap();
+    program_test.add_account(
+        swap_config,
+        Account {
+            lamports: Rent::default().minimum_balance(swap_config_data.len()),
+            data: swap_config_data,
+            owner: swap::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let mut context = program_test.start_with_context().await;
+
+    let token_mint_a = create_mint(&mut context, &token_program_a).await;
+    let token_mint_b = create_mint(&mut context, &token_program_b).await;
+
+    let maker_token_account_a = create_token_account(
+        &mut context,
+        &token_mint_a,
+        &maker.pubkey(),
+        &token_program_a,
+    )
+    .await;
+    let taker_token_account_b = create_token_account(
+        &mut context,
+        &token_mint_b,
+        &taker.pubkey(),
+        &token_program_b,
+    )
+    .await;
+
+    // The fee recipient creates its own token account, takers don't pay for it
+    let fee_recipient_token_account_b = create_token_account(
+        &mut context,
+        &token_mint_b,
+        &fee_recipient,
+        &token_program_b,
+    )
+    .await;
+
+    mint_tokens(
+        &mut context,
+        &token_mint_a,
+        &maker_token_account_a,
+        TOKEN_A_OFFERED_AMOUNT,
+        &token_program_a,
+    )
+    .await;
+    mint_tokens(
+        &mut context,
+        &token_mint_b,
+        &taker_token_account_b,
+        TAKER_TOKEN_B_AMOUNT,
+        &token_program_b,
+    )
+    .await;
+
+    Setup {
+        context,
+        maker,
+        taker,
+        fee_recipient,
+        fee_recipient_token_account_b,
+        swap_config,
+        token_mint_a,
+        token_mint_b,
+        token_program_a,
+        token_program_b,
+        maker_token_account_a,
+        taker_token_account_b,
+    }
+}
+
+// Sends the instructions, paid for by the context payer, and returns the program logs
+async fn try_process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Vec<String>, TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result?;
+    Ok(result.metadata.unwrap().log_messages)
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Vec<String> {
+    try_process(context, instructions, signers).await.unwrap()
+}
+
+fn assert_swap_error<T>(result: Result<T, TransactionError>, error_code: ErrorCode) {
+    match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code))) => {
+            assert_eq!(code, u32::from(error_code))
+        }
+        Err(error) => panic!("expected {error_code:?}, got {error:?}"),
+        Ok(_) => panic!("expected {error_code:?}, but the transaction succeeded"),
+    }
+}
+
+async fn create_mint(context: &mut ProgramTestContext, token_program: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let lamports = Rent::default().minimum_balance(MintState::LEN);
+
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            lamports,
+            MintState::LEN as u64,
+            token_program,
+        ),
+        spl_token_2022::instruction::initialize_mint2(
+            token_program,
+            &mint.pubkey(),
+            &context.payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+
+    process(context, &instructions, &[&mint]).await;
+    mint.pubkey()
+}
+
+// A Token Extensions mint whose permanent delegate, the context payer, can burn from any account
+async fn create_mint_with_permanent_delegate(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::PermanentDelegate])
+            .unwrap();
+    let lamports = Rent::default().minimum_balance(space);
+
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            lamports,
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_permanent_delegate(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &context.payer.pubkey(),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &context.payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+
+    process(context, &instructions, &[&mint]).await;
+    mint.pubkey()
+}
+
+// A Token Extensions mint that withholds a 1% transfer fee
+async fn create_mint_with_transfer_fee(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::TransferFeeConfig])
+            .unwrap();
+    let lamports = Rent::default().minimum_balance(space);
+
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            lamports,
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        transfer_fee_instruction::initialize_transfer_fee_config(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            None,
+            None,
+            100,
+            u64::MAX,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &context.payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+
+    process(context, &instructions, &[&mint]).await;
+    mint.pubkey()
+}
+
+async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    let instruction = spl_associated_token_account::instruction::create_associated_token_account(
+        &context.payer.pubkey(),
+        owner,
+        mint,
+        token_program,
+    );
+
+    process(context, &[instruction], &[]).await;
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+async fn mint_tokens(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    amount: u64,
+    token_program: &Pubkey,
+) {
+    let instruction = spl_token_2022::instruction::mint_to(
+        token_program,
+        mint,
+        token_account,
+        &context.payer.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+
+    process(context, &[instruction], &[]).await;
+}
+
+// Works for both Token Program and Token Extensions Program accounts
+async fn token_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+
+    StateWithExtensions::<TokenAccountState>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+async fn account_exists(context: &mut ProgramTestContext, address: &Pubkey) -> bool {
+    context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .is_some()
+}
+
+// Events are logged as "Program data: <base64 discriminator + borsh data>"
+fn find_event<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Option<T> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .find(|bytes| bytes.len() >= 8 && bytes[..8] == T::DISCRIMINATOR[..])
+        .map(|bytes| T::try_from_slice(&bytes[8..]).unwrap())
+}
+
+fn make_offer_args(id: u64) -> swap::instruction::MakeOffer {
+    swap::instruction::MakeOffer {
+        id,
+        token_a_offered_amount: TOKEN_A_OFFERED_AMOUNT,
+        token_b_wanted_amount: TOKEN_B_WANTED_AMOUNT,
+        options: OfferOptions {
+            expires_at: None,
+            allowed_taker: None,
+            is_collection_offer: false,
+            reimburse_taker_rent: false,
+        },
+    }
+}
+
+fn make_offer_instruction(
+    setup: &Setup,
+    token_mint_b: Pubkey,
+    args: swap::instruction::MakeOffer,
+) -> (Instruction, OfferAccounts) {
+    let maker = setup.maker.pubkey();
+    let offer = Pubkey::find_program_address(
+        &[b"offer", maker.as_ref(), args.id.to_le_bytes().as_ref()],
+        &swap::ID,
+    )
+    .0;
+    let vault = get_associated_token_address_with_program_id(
+        &offer,
+        &setup.token_mint_a,
+        &setup.token_program_a,
+    );
+
+    let instruction = Instruction {
+        program_id: swap::ID,
+        accounts: swap::accounts::MakeOffer {
+            maker,
+            token_mint_a: setup.token_mint_a,
+            token_mint_b,
+            maker_token_account_a: setup.maker_token_account_a,
+            offer,
+            vault,
+            system_program: system_program::ID,
+            token_program_a: setup.token_program_a,
+            token_program_b: setup.token_program_b,
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .to_account_metas(None),
+        data: args.data(),
+    };
+
+    (instruction, OfferAccounts { offer, vault })
+}
+
+async fn make_offer(setup: &mut Setup, args: swap::instruction::MakeOffer) -> OfferAccounts {
+    let id = args.id;
+    let token_a_offered_amount = args.token_a_offered_amount;
+    let token_b_wanted_amount = args.token_b_wanted_amount;
+    let (instruction, offer_accounts) = make_offer_instruction(setup, setup.token_mint_b, args);
+
+    let logs = process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+
+    let offer_made: OfferMade = find_event(&logs).unwrap();
+    assert_eq!(offer_made.offer, offer_accounts.offer);
+    assert_eq!(offer_made.id, id);
+    assert_eq!(offer_made.maker, setup.maker.pubkey());
+    assert_eq!(offer_made.token_a_offered_amount, token_a_offered_amount);
+    assert_eq!(offer_made.token_b_wanted_amount, token_b_wanted_amount);
+
+    offer_accounts
+}
+
+fn take_offer_instruction(
+    setup: &Setup,
+    offer_accounts: &OfferAccounts,
+    fee_recipient_token_account_b: Option<Pubkey>,
+) -> Instruction {
+    let taker = setup.taker.pubkey();
+
+    Instruction {
+        program_id: swap::ID,
+        accounts: swap::accounts::TakeOffer {
+            taker,
+            maker: setup.maker.pubkey(),
+            token_mint_a: setup.token_mint_a,
+            token_mint_b: setup.token_mint_b,
+            token_mint_b_metadata: None,
+            taker_token_account_a: get_associated_token_address_with_program_id(
+                &taker,
+                &setup.token_mint_a,
+                &setup.token_program_a,
+            ),
+            taker_token_account_b: setup.taker_token_account_b,
+            maker_token_account_b: get_associated_token_address_with_program_id(
+                &setup.maker.pubkey(),
+                &setup.token_mint_b,
+                &setup.token_program_b,
+            ),
+            maker_token_account_a: Some(setup.maker_token_account_a),
+            swap_config: setup.swap_config,
+            fee_recipient: setup.fee_recipient,
+            fee_recipient_token_account_b,
+            offer: offer_accounts.offer,
+            vault: offer_accounts.vault,
+            system_program: system_program::ID,
+            token_program_a: setup.token_program_a,
+            token_program_b: setup.token_program_b,
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .to_account_metas(None),
+        // Takers expect the whole vault for the whole wanted amount, at the price the offer was made at
+        data: swap::instruction::TakeOffer {
+            token_b_amount: TOKEN_B_WANTED_AMOUNT,
+            min_token_a_amount: TOKEN_A_OFFERED_AMOUNT,
+        }
+        .data(),
+    }
+}
+
+async fn take_offer(
+    setup: &mut Setup,
+    offer_accounts: &OfferAccounts,
+) -> Result<Vec<String>, TransactionError> {
+    let instruction = take_offer_instruction(
+        setup,
+        offer_accounts,
+        Some(setup.fee_recipient_token_account_b),
+    );
+
+    try_process(&mut setup.context, &[instruction], &[&setup.taker]).await
+}
+
+async fn make_and_take_offer(token_program_a: Pubkey, token_program_b: Pubkey) {
+    let mut setup = setup(token_program_a, token_program_b).await;
+
+    let offer_accounts = make_offer(&mut setup, make_offer_args(1)).await;
+    assert_eq!(
+        token_balance(&mut setup.context, &offer_accounts.vault).await,
+        TOKEN_A_OFFERED_AMOUNT
+    );
+    assert_eq!(
+        token_balance(&mut setup.context, &setup.maker_token_account_a).await,
+        0
+    );
+
+    let logs = take_offer(&mut setup, &offer_accounts).await.unwrap();
+
+    let fee = TOKEN_B_WANTED_AMOUNT * FEE_BASIS_POINTS as u64 / 10_000;
+    let offer_taken: OfferTaken = find_event(&logs).unwrap();
+    assert_eq!(offer_taken.offer, offer_accounts.offer);
+    assert_eq!(offer_taken.taker, setup.taker.pubkey());
+    assert_eq!(offer_taken.token_a_amount, TOKEN_A_OFFERED_AMOUNT);
+    assert_eq!(offer_taken.token_b_amount, TOKEN_B_WANTED_AMOUNT);
+    assert_eq!(offer_taken.fee, fee);
+    assert!(offer_taken.is_filled);
+
+    let taker = setup.taker.pubkey();
+    let maker = setup.maker.pubkey();
+    let taker_token_account_a =
+        get_associated_token_address_with_program_id(&taker, &setup.token_mint_a, &token_program_a);
+    let maker_token_account_b =
+        get_associated_token_address_with_program_id(&maker, &setup.token_mint_b, &token_program_b);
+    let fee_recipient_token_account_b = setup.fee_recipient_token_account_b;
+
+    assert_eq!(
+        token_balance(&mut setup.context, &taker_token_account_a).await,
+        TOKEN_A_OFFERED_AMOUNT
+    );
+    assert_eq!(
+        token_balance(&mut setup.context, &maker_token_account_b).await,
+        TOKEN_B_WANTED_AMOUNT
+    );
+    assert_eq!(
+        token_balance(&mut setup.context, &fee_recipient_token_account_b).await,
+        fee
+    );
+    assert_eq!(
+        token_balance(&mut setup.context, &setup.taker_token_account_b).await,
+        TAKER_TOKEN_B_AMOUNT - TOKEN_B_WANTED_AMOUNT - fee
+    );
+
+    // A complete fill closes both the vault and the offer
+    assert!(!account_exists(&mut setup.context, &offer_accounts.vault).await);
+    assert!(!account_exists(&mut setup.context, &offer_accounts.offer).await);
+}
+
+#[tokio::test]
+async fn makes_and_takes_offer_with_token_program_mints() {
+    make_and_take_offer(anchor_spl::token::ID, anchor_spl::token::ID).await;
+}
+
+#[tokio::test]
+async fn makes_and_takes_offer_with_token_2022_mints() {
+    make_and_take_offer(spl_token_2022::ID, spl_token_2022::ID).await;
+}
+
+#[tokio::test]
+async fn makes_and_takes_offer_with_mixed_token_programs() {
+    make_and_take_offer(spl_token_2022::ID, anchor_spl::token::ID).await;
+}
+
+#[tokio::test]
+async fn refunds_offer_to_maker() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+    let offer_accounts = make_offer(&mut setup, make_offer_args(2)).await;
+
+    let instruction = Instruction {
+        program_id: swap::ID,
+        accounts: swap::accounts::RefundOffer {
+            maker: setup.maker.pubkey(),
+            token_mint_a: setup.token_mint_a,
+            maker_token_account_a: setup.maker_token_account_a,
+            offer: offer_accounts.offer,
+            vault: offer_accounts.vault,
+            system_program: system_program::ID,
+            token_program_a: setup.token_program_a,
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .to_account_metas(None),
+        data: swap::instruction::RefundOffer {}.data(),
+    };
+
+    let logs = process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+
+    let offer_cancelled: OfferCancelled = find_event(&logs).unwrap();
+    assert_eq!(offer_cancelled.offer, offer_accounts.offer);
+    assert_eq!(
+        offer_cancelled.token_a_refunded_amount,
+        TOKEN_A_OFFERED_AMOUNT
+    );
+
+    assert_eq!(
+        token_balance(&mut setup.context, &setup.maker_token_account_a).await,
+        TOKEN_A_OFFERED_AMOUNT
+    );
+    assert!(!account_exists(&mut setup.context, &offer_accounts.vault).await);
+    assert!(!account_exists(&mut setup.context, &offer_accounts.offer).await);
+}
+
+#[tokio::test]
+async fn rejects_offers_with_zero_amounts() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+
+    let mut args = make_offer_args(3);
+    args.token_a_offered_amount = 0;
+    let (instruction, _) = make_offer_instruction(&setup, setup.token_mint_b, args);
+    let result = try_process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+    assert_swap_error(result, ErrorCode::InvalidOfferAmount);
+
+    let mut args = make_offer_args(4);
+    args.token_b_wanted_amount = 0;
+    let (instruction, _) = make_offer_instruction(&setup, setup.token_mint_b, args);
+    let result = try_process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+    assert_swap_error(result, ErrorCode::InvalidOfferAmount);
+}
+
+#[tokio::test]
+async fn rejects_offers_for_the_same_token() {
+    let mut setup = setup(spl_token_2022::ID, spl_token_2022::ID).await;
+
+    let (instruction, _) = make_offer_instruction(&setup, setup.token_mint_a, make_offer_args(5));
+    let result = try_process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+    assert_swap_error(result, ErrorCode::IdenticalTokenMints);
+}
+
+#[tokio::test]
+async fn rejects_reusing_the_id_of_an_open_offer() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+
+    let mut args = make_offer_args(6);
+    args.token_a_offered_amount = TOKEN_A_OFFERED_AMOUNT / 2;
+    make_offer(&mut setup, args).await;
+
+    // Same id, different amounts so the transaction isn't a duplicate of the first one
+    let mut args = make_offer_args(6);
+    args.token_a_offered_amount = TOKEN_A_OFFERED_AMOUNT / 4;
+    let (instruction, _) = make_offer_instruction(&setup, setup.token_mint_b, args);
+    let result = try_process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+
+    // The offer account can't be created a second time. This System Program error is the
+    // documented result of reusing an id, the swap program has no error of its own for it.
+    assert_eq!(
+        result.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(SystemError::AccountAlreadyInUse as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn rejects_takes_larger_than_the_vault_balance() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+
+    // Burning from the vault with the permanent delegate leaves it holding less than the offer
+    setup.token_mint_a = create_mint_with_permanent_delegate(&mut setup.context).await;
+    setup.maker_token_account_a = create_token_account(
+        &mut setup.context,
+        &setup.token_mint_a,
+        &setup.maker.pubkey(),
+        &spl_token_2022::ID,
+    )
+    .await;
+    mint_tokens(
+        &mut setup.context,
+        &setup.token_mint_a,
+        &setup.maker_token_account_a,
+        TOKEN_A_OFFERED_AMOUNT,
+        &spl_token_2022::ID,
+    )
+    .await;
+
+    let offer_accounts = make_offer(&mut setup, make_offer_args(7)).await;
+
+    let burn = spl_token_2022::instruction::burn_checked(
+        &spl_token_2022::ID,
+        &offer_accounts.vault,
+        &setup.token_mint_a,
+        &setup.context.payer.pubkey(),
+        &[],
+        1,
+        6,
+    )
+    .unwrap();
+    process(&mut setup.context, &[burn], &[]).await;
+
+    let result = take_offer(&mut setup, &offer_accounts).await;
+    assert_swap_error(result, ErrorCode::InsufficientVaultBalance);
+}
+
+#[tokio::test]
+async fn rejects_takers_other_than_the_allowed_taker() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+
+    let mut args = make_offer_args(8);
+    args.options.allowed_taker = Some(Pubkey::new_unique());
+    let offer_accounts = make_offer(&mut setup, args).await;
+
+    let result = take_offer(&mut setup, &offer_accounts).await;
+    assert_swap_error(result, ErrorCode::TakerNotAllowed);
+}
+
+#[tokio::test]
+async fn rejects_takes_charged_a_fee_without_the_fee_recipient_token_account() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+    let offer_accounts = make_offer(&mut setup, make_offer_args(9)).await;
+
+    let instruction = take_offer_instruction(&setup, &offer_accounts, None);
+    let result = try_process(&mut setup.context, &[instruction], &[&setup.taker]).await;
+    assert_swap_error(result, ErrorCode::MissingFeeRecipientTokenAccount);
+}
+
+#[tokio::test]
+async fn rejects_takes_after_the_maker_worsens_the_price() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+    let offer_accounts = make_offer(&mut setup, make_offer_args(10)).await;
+
+    // The maker's update lands before the take, halving what the wanted amount buys
+    let update_instruction = Instruction {
+        program_id: swap::ID,
+        accounts: swap::accounts::UpdateOffer {
+            maker: setup.maker.pubkey(),
+            token_mint_a: setup.token_mint_a,
+            maker_token_account_a: setup.maker_token_account_a,
+            offer: offer_accounts.offer,
+            vault: offer_accounts.vault,
+            token_program_a: setup.token_program_a,
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .to_account_metas(None),
+        data: swap::instruction::UpdateOffer {
+            changes: OfferChanges {
+                token_b_wanted_amount: Some(TOKEN_B_WANTED_AMOUNT * 2),
+                token_a_deposit_amount: 0,
+                token_a_withdraw_amount: 0,
+                expires_at: None,
+            },
+        }
+        .data(),
+    };
+    process(&mut setup.context, &[update_instruction], &[&setup.maker]).await;
+
+    let result = take_offer(&mut setup, &offer_accounts).await;
+    assert_swap_error(result, ErrorCode::SlippageExceeded);
+
+    // Nothing moved, the offer is still open with the whole vault
+    assert_eq!(
+        token_balance(&mut setup.context, &offer_accounts.vault).await,
+        TOKEN_A_OFFERED_AMOUNT
+    );
+    assert_eq!(
+        token_balance(&mut setup.context, &setup.taker_token_account_b).await,
+        TAKER_TOKEN_B_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn fills_offers_whose_vault_was_sent_extra_tokens() {
+    let mut setup = setup(spl_token_2022::ID, anchor_spl::token::ID).await;
+    let offer_accounts = make_offer(&mut setup, make_offer_args(11)).await;
+
+    // Anyone can send dust to the vault, which would otherwise keep it from closing
+    mint_tokens(
+        &mut setup.context,
+        &setup.token_mint_a,
+        &offer_accounts.vault,
+        1,
+        &setup.token_program_a,
+    )
+    .await;
+
+    let logs = take_offer(&mut setup, &offer_accounts).await.unwrap();
+    let offer_taken: OfferTaken = find_event(&logs).unwrap();
+    assert_eq!(offer_taken.token_a_amount, TOKEN_A_OFFERED_AMOUNT);
+    assert!(offer_taken.is_filled);
+
+    // The dust goes back to the maker and the fill closes both accounts
+    assert_eq!(
+        token_balance(&mut setup.context, &setup.maker_token_account_a).await,
+        1
+    );
+    assert!(!account_exists(&mut setup.context, &offer_accounts.vault).await);
+    assert!(!account_exists(&mut setup.context, &offer_accounts.offer).await);
+}
+
+#[tokio::test]
+async fn rejects_offers_wanting_a_transfer_fee_token() {
+    let mut setup = setup(spl_token_2022::ID, spl_token_2022::ID).await;
+
+    // The maker would be paid the wanted amount less the withheld fee
+    let token_mint_b = create_mint_with_transfer_fee(&mut setup.context).await;
+    let (instruction, _) = make_offer_instruction(&setup, token_mint_b, make_offer_args(12));
+    let result = try_process(&mut setup.context, &[instruction], &[&setup.maker]).await;
+    assert_swap_error(result, ErrorCode::TransferFeeNotSupported);
+}
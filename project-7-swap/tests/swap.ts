@@ -3,8 +3,11 @@ import * as anchor from "@coral-xyz/anchor";
 import { BN, type Program } from "@coral-xyz/anchor";
 import {
   TOKEN_2022_PROGRAM_ID,
-  type TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
   getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
 } from "@solana/spl-token";
 import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
 import { assert } from "chai";
@@ -46,7 +49,8 @@ describe("swap", async () => {
 
   // We're going to reuse these accounts across multiple tests
   const accounts: Record<string, PublicKey> = {
-    tokenProgram: TOKEN_PROGRAM,
+    tokenProgramA: TOKEN_PROGRAM,
+    tokenProgramB: TOKEN_PROGRAM,
   };
 
   let alice: anchor.web3.Keypair;
@@ -59,6 +63,17 @@ describe("swap", async () => {
   const tokenAOfferedAmount = new BN(1_000_000);
   const tokenBWantedAmount = new BN(1_000_000);
 
+  // Public offers for a single token B mint that never expire
+  const defaultOfferOptions = {
+    expiresAt: null,
+    allowedTaker: null,
+    isCollectionOffer: false,
+    reimburseTakerRent: false,
+  };
+
+  // Protocol fee paid by takers, on top of the token B sent to the maker
+  const feeBasisPoints = 30;
+
   before(
     "Creates Alice and Bob accounts, 2 token mints, and associated token accounts for both tokens for both users",
     async () => {
@@ -113,6 +128,46 @@ describe("swap", async () => {
     }
   );
 
+  it("Lets the upgrade authority create the swap config", async () => {
+    const programData = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    )[0];
+
+    accounts.feeRecipient = payer.publicKey;
+
+    const transactionSignature = await program.methods
+      .initializeSwapConfig(feeBasisPoints, accounts.feeRecipient)
+      .accounts({ admin: payer.publicKey, programData })
+      .rpc();
+
+    await confirmTransaction(connection, transactionSignature);
+
+    const swapConfig = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    )[0];
+    const swapConfigAccount = await program.account.swapConfig.fetch(
+      swapConfig
+    );
+    assert(swapConfigAccount.admin.equals(payer.publicKey));
+    assert.equal(swapConfigAccount.feeBasisPoints, feeBasisPoints);
+    assert(swapConfigAccount.feeRecipient.equals(accounts.feeRecipient));
+
+    // The fee recipient creates its own token accounts, takers don't pay for them
+    const feeRecipientTokenAccountB = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      accounts.tokenMintB,
+      accounts.feeRecipient,
+      false,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM
+    );
+    accounts.feeRecipientTokenAccountB = feeRecipientTokenAccountB.address;
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
   it("Puts the tokens Alice offers into the vault when Alice makes an offer", async () => {
     // Pick a random ID for the offer we'll make
     const offerId = getRandomBigNumber();
@@ -138,7 +193,12 @@ describe("swap", async () => {
     accounts.vault = vault;
 
     const transactionSignature = await program.methods
-      .makeOffer(offerId, tokenAOfferedAmount, tokenBWantedAmount)
+      .makeOffer(
+        offerId,
+        tokenAOfferedAmount,
+        tokenBWantedAmount,
+        defaultOfferOptions
+      )
       .accounts({ ...accounts })
       .signers([alice])
       .rpc();
@@ -159,9 +219,57 @@ describe("swap", async () => {
     assert(offerAccount.tokenBWantedAmount.eq(tokenBWantedAmount));
   }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
 
+  it("Keeps the offer open with the remaining amounts when Bob partially fills it", async () => {
+    const tokenBPartialAmount = tokenBWantedAmount.divn(4);
+
+    const transactionSignature = await program.methods
+      .takeOffer(tokenBPartialAmount, tokenAOfferedAmount.divn(4))
+      .accounts({ ...accounts })
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, transactionSignature);
+
+    // Bob receives the same proportion of token A as the token B he paid
+    const bobTokenAccountBalanceResponse =
+      await connection.getTokenAccountBalance(accounts.takerTokenAccountA);
+    const bobTokenAccountBalance = new BN(
+      bobTokenAccountBalanceResponse.value.amount
+    );
+    assert(bobTokenAccountBalance.eq(tokenAOfferedAmount.divn(4)));
+
+    // The fee recipient receives the protocol fee in token B
+    const feeRecipientBalanceResponse = await connection.getTokenAccountBalance(
+      accounts.feeRecipientTokenAccountB
+    );
+    const feeRecipientBalance = new BN(feeRecipientBalanceResponse.value.amount);
+    assert(
+      feeRecipientBalance.eq(
+        tokenBPartialAmount.muln(feeBasisPoints).divn(10_000)
+      )
+    );
+
+    const offerAccount = await program.account.offer.fetch(accounts.offer);
+    assert(
+      offerAccount.tokenARemainingAmount.eq(
+        tokenAOfferedAmount.sub(tokenAOfferedAmount.divn(4))
+      )
+    );
+    assert(
+      offerAccount.tokenBRemainingAmount.eq(
+        tokenBWantedAmount.sub(tokenBPartialAmount)
+      )
+    );
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
   it("Puts the tokens from the vault into Bob's account, and gives Alice Bob's tokens, when Bob takes an offer", async () => {
+    const offerAccount = await program.account.offer.fetch(accounts.offer);
+
     const transactionSignature = await program.methods
-      .takeOffer()
+      .takeOffer(
+        offerAccount.tokenBRemainingAmount,
+        offerAccount.tokenARemainingAmount
+      )
       .accounts({ ...accounts })
       .signers([bob])
       .rpc();
@@ -185,5 +293,1225 @@ describe("swap", async () => {
       aliceTokenAccountBalanceAfterResponse.value.amount
     );
     assert(aliceTokenAccountBalanceAfter.eq(tokenBWantedAmount));
+
+    // The offer is closed once it is fully filled
+    const offerAccountInfo = await connection.getAccountInfo(accounts.offer);
+    assert(offerAccountInfo === null);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Returns the vault balance to Alice and closes the offer when Alice refunds an offer", async () => {
+    const offerId = getRandomBigNumber();
+
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const vault = getAssociatedTokenAddressSync(
+      accounts.tokenMintA,
+      offer,
+      true,
+      TOKEN_PROGRAM
+    );
+
+    accounts.offer = offer;
+    accounts.vault = vault;
+
+    const aliceBalanceBeforeResponse = await connection.getTokenAccountBalance(
+      accounts.makerTokenAccountA
+    );
+    const aliceBalanceBefore = new BN(aliceBalanceBeforeResponse.value.amount);
+
+    const makeOfferSignature = await program.methods
+      .makeOffer(
+        offerId,
+        tokenAOfferedAmount,
+        tokenBWantedAmount,
+        defaultOfferOptions
+      )
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    const refundOfferSignature = await program.methods
+      .refundOffer()
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, refundOfferSignature);
+
+    // Alice has all her offered tokens back
+    const aliceBalanceAfterResponse = await connection.getTokenAccountBalance(
+      accounts.makerTokenAccountA
+    );
+    const aliceBalanceAfter = new BN(aliceBalanceAfterResponse.value.amount);
+    assert(aliceBalanceAfter.eq(aliceBalanceBefore));
+
+    // Both the vault and the offer are closed
+    assert((await connection.getAccountInfo(vault)) === null);
+    assert((await connection.getAccountInfo(offer)) === null);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Lets Alice change the price, vault balance and expiry of an open offer", async () => {
+    const offerId = getRandomBigNumber();
+
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const vault = getAssociatedTokenAddressSync(
+      accounts.tokenMintA,
+      offer,
+      true,
+      TOKEN_PROGRAM
+    );
+
+    accounts.offer = offer;
+    accounts.vault = vault;
+
+    const now = await connection.getBlockTime(await connection.getSlot());
+    const expiresAt = new BN(now + 60);
+
+    const makeOfferSignature = await program.methods
+      .makeOffer(
+        offerId,
+        tokenAOfferedAmount,
+        tokenBWantedAmount,
+        { ...defaultOfferOptions, expiresAt }
+      )
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    // Alice asks for twice as much token B, takes back a quarter of the vault,
+    // and gives takers another minute
+    const newTokenBWantedAmount = tokenBWantedAmount.muln(2);
+    const withdrawAmount = tokenAOfferedAmount.divn(4);
+    const newExpiresAt = expiresAt.addn(60);
+
+    const updateOfferSignature = await program.methods
+      .updateOffer({
+        tokenBWantedAmount: newTokenBWantedAmount,
+        tokenADepositAmount: new BN(0),
+        tokenAWithdrawAmount: withdrawAmount,
+        expiresAt: newExpiresAt,
+      })
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, updateOfferSignature);
+
+    const vaultBalanceResponse = await connection.getTokenAccountBalance(vault);
+    const vaultBalance = new BN(vaultBalanceResponse.value.amount);
+    assert(vaultBalance.eq(tokenAOfferedAmount.sub(withdrawAmount)));
+
+    const offerAccount = await program.account.offer.fetch(offer);
+    assert(offerAccount.tokenAOfferedAmount.eq(vaultBalance));
+    assert(offerAccount.tokenARemainingAmount.eq(vaultBalance));
+    assert(offerAccount.tokenBWantedAmount.eq(newTokenBWantedAmount));
+    assert(offerAccount.tokenBRemainingAmount.eq(newTokenBWantedAmount));
+    assert(offerAccount.expiresAt.eq(newExpiresAt));
+
+    // The expiry can only move later
+    try {
+      await program.methods
+        .updateOffer({
+          tokenBWantedAmount: null,
+          tokenADepositAmount: withdrawAmount,
+          tokenAWithdrawAmount: new BN(0),
+          expiresAt,
+        })
+        .accounts({ ...accounts })
+        .signers([alice])
+        .rpc();
+      assert.fail("Shortening the expiry should fail");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "ExpiryNotExtended");
+    }
+
+    // Topping the vault back up keeps the new price
+    const topUpSignature = await program.methods
+      .updateOffer({
+        tokenBWantedAmount: null,
+        tokenADepositAmount: withdrawAmount,
+        tokenAWithdrawAmount: new BN(0),
+        expiresAt: null,
+      })
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, topUpSignature);
+
+    const toppedUpOfferAccount = await program.account.offer.fetch(offer);
+    assert(toppedUpOfferAccount.tokenAOfferedAmount.eq(tokenAOfferedAmount));
+    assert(toppedUpOfferAccount.tokenBWantedAmount.eq(newTokenBWantedAmount));
+    assert(toppedUpOfferAccount.expiresAt.eq(newExpiresAt));
+
+    // Bob saw the offer before Alice raised the price, and expects the whole vault
+    // for the original wanted amount, so his take fails rather than fill at the new price
+    try {
+      await program.methods
+        .takeOffer(tokenBWantedAmount, tokenAOfferedAmount)
+        .accounts({ ...accounts })
+        .signers([bob])
+        .rpc();
+      assert.fail("A take below the minimum token A amount should fail");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "SlippageExceeded");
+    }
+
+    const refundOfferSignature = await program.methods
+      .refundOffer()
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, refundOfferSignature);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Rejects takes after expiry, and lets anyone close an expired offer back to Alice", async () => {
+    const offerId = getRandomBigNumber();
+
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const vault = getAssociatedTokenAddressSync(
+      accounts.tokenMintA,
+      offer,
+      true,
+      TOKEN_PROGRAM
+    );
+
+    accounts.offer = offer;
+    accounts.vault = vault;
+
+    const aliceBalanceBeforeResponse = await connection.getTokenAccountBalance(
+      accounts.makerTokenAccountA
+    );
+    const aliceBalanceBefore = new BN(aliceBalanceBeforeResponse.value.amount);
+
+    // Expire the offer a couple of seconds from now, using the cluster's clock
+    const now = await connection.getBlockTime(await connection.getSlot());
+    const expiresAt = new BN(now + 2);
+
+    const makeOfferSignature = await program.methods
+      .makeOffer(
+        offerId,
+        tokenAOfferedAmount,
+        tokenBWantedAmount,
+        { ...defaultOfferOptions, expiresAt }
+      )
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    await new Promise((resolve) => setTimeout(resolve, 5 * SECONDS));
+
+    try {
+      await program.methods
+        .takeOffer(tokenBWantedAmount, tokenAOfferedAmount)
+        .accounts({ ...accounts })
+        .signers([bob])
+        .rpc();
+      assert.fail("Taking an expired offer should fail");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "OfferExpired");
+    }
+
+    // Bob cranks the expired offer closed, Alice gets everything back
+    const closeExpiredOfferSignature = await program.methods
+      .closeExpiredOffer()
+      .accounts({ ...accounts, cranker: bob.publicKey })
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, closeExpiredOfferSignature);
+
+    const aliceBalanceAfterResponse = await connection.getTokenAccountBalance(
+      accounts.makerTokenAccountA
+    );
+    const aliceBalanceAfter = new BN(aliceBalanceAfterResponse.value.amount);
+    assert(aliceBalanceAfter.eq(aliceBalanceBefore));
+
+    assert((await connection.getAccountInfo(vault)) === null);
+    assert((await connection.getAccountInfo(offer)) === null);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Only lets the allowed taker fill a private offer", async () => {
+    const offerId = getRandomBigNumber();
+
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const vault = getAssociatedTokenAddressSync(
+      accounts.tokenMintA,
+      offer,
+      true,
+      TOKEN_PROGRAM
+    );
+
+    accounts.offer = offer;
+    accounts.vault = vault;
+
+    // Alice negotiated this deal with someone other than Bob
+    const [allowedTaker] = makeKeypairs(1);
+
+    const makeOfferSignature = await program.methods
+      .makeOffer(
+        offerId,
+        tokenAOfferedAmount,
+        tokenBWantedAmount,
+        { ...defaultOfferOptions, allowedTaker: allowedTaker.publicKey }
+      )
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    const offerAccount = await program.account.offer.fetch(offer);
+    assert(offerAccount.allowedTaker.equals(allowedTaker.publicKey));
+
+    try {
+      await program.methods
+        .takeOffer(tokenBWantedAmount, tokenAOfferedAmount)
+        .accounts({ ...accounts })
+        .signers([bob])
+        .rpc();
+      assert.fail("Bob should not be able to take a private offer");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "TakerNotAllowed");
+    }
+
+    const refundOfferSignature = await program.methods
+      .refundOffer()
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, refundOfferSignature);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Swaps a legacy SPL token for a Token Extensions token", async () => {
+    // Alice offers a token from the original Token Program
+    const legacyMint = await createMint(
+      connection,
+      payer,
+      payer.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const aliceLegacyTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      legacyMint,
+      alice.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      payer,
+      legacyMint,
+      aliceLegacyTokenAccount.address,
+      payer,
+      tokenAOfferedAmount.toNumber(),
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+
+    const offerId = getRandomBigNumber();
+
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const mixedAccounts = {
+      ...accounts,
+      tokenMintA: legacyMint,
+      makerTokenAccountA: aliceLegacyTokenAccount.address,
+      takerTokenAccountA: getAssociatedTokenAddressSync(
+        legacyMint,
+        bob.publicKey,
+        false,
+        TOKEN_PROGRAM_ID
+      ),
+      offer,
+      vault: getAssociatedTokenAddressSync(
+        legacyMint,
+        offer,
+        true,
+        TOKEN_PROGRAM_ID
+      ),
+      tokenProgramA: TOKEN_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
+    };
+
+    const makeOfferSignature = await program.methods
+      .makeOffer(
+        offerId,
+        tokenAOfferedAmount,
+        tokenBWantedAmount,
+        defaultOfferOptions
+      )
+      .accounts(mixedAccounts)
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    const takeOfferSignature = await program.methods
+      .takeOffer(tokenBWantedAmount, tokenAOfferedAmount)
+      .accounts(mixedAccounts)
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, takeOfferSignature);
+
+    const bobLegacyBalanceResponse = await connection.getTokenAccountBalance(
+      mixedAccounts.takerTokenAccountA
+    );
+    const bobLegacyBalance = new BN(bobLegacyBalanceResponse.value.amount);
+    assert(bobLegacyBalance.eq(tokenAOfferedAmount));
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Matches Bob's bid against Alice's asks on the order book, best price first", async () => {
+    // Token A is the base token and token B the quote token.
+    // Prices are in token B per lot of token A.
+    const baseLotSize = new BN(1_000);
+
+    const market = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("market"),
+        accounts.tokenMintA.toBuffer(),
+        accounts.tokenMintB.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+    const getOpenOrders = (owner: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("open_orders"), market.toBuffer(), owner.toBuffer()],
+        program.programId
+      )[0];
+
+    const marketAccounts: Record<string, PublicKey> = {
+      baseMint: accounts.tokenMintA,
+      quoteMint: accounts.tokenMintB,
+      market,
+      baseVault: getAssociatedTokenAddressSync(
+        accounts.tokenMintA,
+        market,
+        true,
+        TOKEN_PROGRAM
+      ),
+      quoteVault: getAssociatedTokenAddressSync(
+        accounts.tokenMintB,
+        market,
+        true,
+        TOKEN_PROGRAM
+      ),
+      baseTokenProgram: TOKEN_PROGRAM,
+      quoteTokenProgram: TOKEN_PROGRAM,
+    };
+    const aliceAccounts = {
+      ...marketAccounts,
+      owner: alice.publicKey,
+      openOrders: getOpenOrders(alice.publicKey),
+      ownerBaseAccount: accounts.makerTokenAccountA,
+      ownerQuoteAccount: accounts.makerTokenAccountB,
+    };
+    const bobAccounts = {
+      ...marketAccounts,
+      owner: bob.publicKey,
+      openOrders: getOpenOrders(bob.publicKey),
+      ownerBaseAccount: accounts.takerTokenAccountA,
+      ownerQuoteAccount: accounts.takerTokenAccountB,
+    };
+
+    // Orders smaller than 10 lots are rejected, so dust can't fill up the book
+    const minOrderQuantity = new BN(10);
+
+    await program.methods
+      .createMarket(baseLotSize, minOrderQuantity)
+      .accounts({ ...marketAccounts, payer: payer.publicKey })
+      .rpc();
+    await program.methods
+      .createOpenOrders()
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+    await program.methods
+      .createOpenOrders()
+      .accounts(bobAccounts)
+      .signers([bob])
+      .rpc();
+
+    // Alice posts the worse ask first, so the book has to order them by price
+    await program.methods
+      .placeOrder({ ask: {} }, new BN(3_000), new BN(100))
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+    await program.methods
+      .placeOrder({ ask: {} }, new BN(2_000), new BN(100))
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+
+    try {
+      await program.methods
+        .placeOrder({ bid: {} }, new BN(1_000), new BN(1))
+        .accounts(bobAccounts)
+        .signers([bob])
+        .rpc();
+      assert.fail("An order below the minimum quantity should not be posted");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "OrderTooSmall");
+    }
+
+    // A bid at or above the best ask must be taken rather than posted
+    try {
+      await program.methods
+        .placeOrder({ bid: {} }, new BN(2_000), minOrderQuantity)
+        .accounts(bobAccounts)
+        .signers([bob])
+        .rpc();
+      assert.fail("A crossing order should not be posted");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "OrderWouldCross");
+    }
+
+    const bobQuoteBalanceBeforeResponse = await connection.getTokenAccountBalance(
+      accounts.takerTokenAccountB
+    );
+    const bobQuoteBalanceBefore = new BN(
+      bobQuoteBalanceBeforeResponse.value.amount
+    );
+
+    // Bob sweeps 150 lots: all of the ask at 2_000, half of the ask at 3_000.
+    // Each filled order needs its maker's open orders account.
+    const takeOrdersSignature = await program.methods
+      .takeOrders({ bid: {} }, new BN(150), new BN(3_000))
+      .accounts(bobAccounts)
+      .remainingAccounts([
+        { pubkey: aliceAccounts.openOrders, isSigner: false, isWritable: true },
+        { pubkey: aliceAccounts.openOrders, isSigner: false, isWritable: true },
+      ])
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, takeOrdersSignature);
+
+    const quoteAmountFilled = new BN(100 * 2_000 + 50 * 3_000);
+
+    const bobQuoteBalanceAfterResponse = await connection.getTokenAccountBalance(
+      accounts.takerTokenAccountB
+    );
+    const bobQuoteBalanceAfter = new BN(
+      bobQuoteBalanceAfterResponse.value.amount
+    );
+    assert(
+      bobQuoteBalanceBefore.sub(bobQuoteBalanceAfter).eq(quoteAmountFilled)
+    );
+
+    const bobOpenOrders = await program.account.openOrders.fetch(
+      bobAccounts.openOrders
+    );
+    assert(bobOpenOrders.baseFree.eq(baseLotSize.muln(150)));
+
+    const aliceOpenOrders = await program.account.openOrders.fetch(
+      aliceAccounts.openOrders
+    );
+    assert(aliceOpenOrders.quoteFree.eq(quoteAmountFilled));
+
+    const marketAccount = await program.account.market.fetch(market);
+    assert(marketAccount.askCount.eqn(1));
+    assert(marketAccount.asks[0].price.eqn(3_000));
+    assert(marketAccount.asks[0].quantity.eqn(50));
+
+    // Alice cancels what is left of her ask, then both settle their balances
+    await program.methods
+      .cancelOrder({ ask: {} }, marketAccount.asks[0].orderId)
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+
+    const aliceBaseBalanceBeforeResponse = await connection.getTokenAccountBalance(
+      accounts.makerTokenAccountA
+    );
+    const aliceBaseBalanceBefore = new BN(
+      aliceBaseBalanceBeforeResponse.value.amount
+    );
+
+    await program.methods
+      .settleFunds()
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+    await program.methods
+      .settleFunds()
+      .accounts(bobAccounts)
+      .signers([bob])
+      .rpc();
+
+    const aliceBaseBalanceAfterResponse = await connection.getTokenAccountBalance(
+      accounts.makerTokenAccountA
+    );
+    const aliceBaseBalanceAfter = new BN(
+      aliceBaseBalanceAfterResponse.value.amount
+    );
+    assert(
+      aliceBaseBalanceAfter.sub(aliceBaseBalanceBefore).eq(baseLotSize.muln(50))
+    );
+
+    const aliceOpenOrdersAfter = await program.account.openOrders.fetch(
+      aliceAccounts.openOrders
+    );
+    assert(aliceOpenOrdersAfter.baseFree.isZero());
+    assert(aliceOpenOrdersAfter.quoteFree.isZero());
+
+    const vaultBalanceResponse = await connection.getTokenAccountBalance(
+      marketAccounts.baseVault
+    );
+    assert.equal(vaultBalanceResponse.value.amount, "0");
+
+    // Bob fills every bid slot, the worst bid is at 10
+    const maxOrdersPerSide = 64;
+    for (let index = 0; index < maxOrdersPerSide; index++) {
+      await program.methods
+        .placeOrder({ bid: {} }, new BN(10 + index), minOrderQuantity)
+        .accounts(bobAccounts)
+        .signers([bob])
+        .rpc();
+    }
+
+    // A full side only takes orders priced better than its worst order
+    try {
+      await program.methods
+        .placeOrder({ bid: {} }, new BN(10), minOrderQuantity)
+        .accounts(bobAccounts)
+        .signers([bob])
+        .rpc();
+      assert.fail("An order no better than the worst order should not be posted");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "OrderBookFull");
+    }
+
+    // Alice's better bid evicts Bob's worst bid, whose funds are unlocked for Bob to settle
+    await program.methods
+      .placeOrder({ bid: {} }, new BN(100), minOrderQuantity)
+      .accounts({ ...aliceAccounts, evictedOpenOrders: bobAccounts.openOrders })
+      .signers([alice])
+      .rpc();
+
+    const fullMarketAccount = await program.account.market.fetch(market);
+    assert(fullMarketAccount.bidCount.eqn(maxOrdersPerSide));
+    assert(fullMarketAccount.bids[0].price.eqn(100));
+    assert(fullMarketAccount.bids[maxOrdersPerSide - 1].price.eqn(11));
+
+    const bobOpenOrdersAfterEviction = await program.account.openOrders.fetch(
+      bobAccounts.openOrders
+    );
+    assert(bobOpenOrdersAfterEviction.quoteFree.eq(minOrderQuantity.muln(10)));
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Lets Alice provide liquidity to a pool that Bob swaps against", async () => {
+    const poolFeeBasisPoints = 30;
+
+    const pool = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        accounts.tokenMintA.toBuffer(),
+        accounts.tokenMintB.toBuffer(),
+      ],
+      program.programId
+    )[0];
+    const lpMint = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), pool.toBuffer()],
+      program.programId
+    )[0];
+
+    const poolAccounts: Record<string, PublicKey> = {
+      tokenMintA: accounts.tokenMintA,
+      tokenMintB: accounts.tokenMintB,
+      pool,
+      lpMint,
+      vaultA: getAssociatedTokenAddressSync(
+        accounts.tokenMintA,
+        pool,
+        true,
+        TOKEN_PROGRAM
+      ),
+      vaultB: getAssociatedTokenAddressSync(
+        accounts.tokenMintB,
+        pool,
+        true,
+        TOKEN_PROGRAM
+      ),
+      tokenProgramA: TOKEN_PROGRAM,
+      tokenProgramB: TOKEN_PROGRAM,
+      lpTokenProgram: TOKEN_PROGRAM,
+    };
+    const aliceLpTokenAccount = getAssociatedTokenAddressSync(
+      lpMint,
+      alice.publicKey,
+      false,
+      TOKEN_PROGRAM
+    );
+    const aliceAccounts = {
+      ...poolAccounts,
+      provider: alice.publicKey,
+      providerTokenAccountA: accounts.makerTokenAccountA,
+      providerTokenAccountB: accounts.makerTokenAccountB,
+      providerLpTokenAccount: aliceLpTokenAccount,
+    };
+    const bobAccounts = {
+      ...poolAccounts,
+      trader: bob.publicKey,
+      traderTokenAccountA: accounts.takerTokenAccountA,
+      traderTokenAccountB: accounts.takerTokenAccountB,
+    };
+
+    await program.methods
+      .createPool(poolFeeBasisPoints)
+      .accounts({ ...poolAccounts, payer: payer.publicKey })
+      .rpc();
+
+    // The first deposit mints sqrt(100_000 * 100_000),
+    // less the minimum liquidity locked in the pool
+    const addLiquiditySignature = await program.methods
+      .addLiquidity(new BN(100_000), new BN(100_000), new BN(99_000))
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, addLiquiditySignature);
+
+    const aliceLpBalanceResponse = await connection.getTokenAccountBalance(
+      aliceLpTokenAccount
+    );
+    assert.equal(aliceLpBalanceResponse.value.amount, "99000");
+
+    // 10_000 token B in, less the 0.3% fee, against 100_000 of each token:
+    // 100_000 * 9_970 / (100_000 + 9_970) = 9_066 token A out
+    const expectedAmountOut = new BN(9_066);
+
+    try {
+      await program.methods
+        .swapExactIn(false, new BN(10_000), expectedAmountOut.addn(1))
+        .accounts(bobAccounts)
+        .signers([bob])
+        .rpc();
+      assert.fail("The swap should not pay out less than the minimum");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "SlippageExceeded");
+    }
+
+    const bobBalanceBeforeResponse = await connection.getTokenAccountBalance(
+      accounts.takerTokenAccountA
+    );
+    const bobBalanceBefore = new BN(bobBalanceBeforeResponse.value.amount);
+
+    const swapSignature = await program.methods
+      .swapExactIn(false, new BN(10_000), expectedAmountOut)
+      .accounts(bobAccounts)
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, swapSignature);
+
+    const bobBalanceAfterResponse = await connection.getTokenAccountBalance(
+      accounts.takerTokenAccountA
+    );
+    const bobBalanceAfter = new BN(bobBalanceAfterResponse.value.amount);
+    assert(bobBalanceAfter.sub(bobBalanceBefore).eq(expectedAmountOut));
+
+    // Alice withdraws her whole share, which now includes the fee Bob paid
+    const removeLiquiditySignature = await program.methods
+      .removeLiquidity(new BN(99_000), new BN(0), new BN(0))
+      .accounts(aliceAccounts)
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, removeLiquiditySignature);
+
+    const vaultBBalanceResponse = await connection.getTokenAccountBalance(
+      poolAccounts.vaultB
+    );
+    // 110_000 token B in the vault, of which Alice owns 99_000 / 100_000
+    assert.equal(vaultBBalanceResponse.value.amount, "1100");
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert(poolAccount.lpSupply.eqn(1_000));
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Trades a basket of tokens A and B for a legacy SPL token in one transaction", async () => {
+    // Bob holds a token from the original Token Program that Alice wants
+    const legacyMint = await createMint(
+      connection,
+      payer,
+      payer.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const bobLegacyTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      legacyMint,
+      bob.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const legacyWantedAmount = new BN(500);
+    await mintTo(
+      connection,
+      payer,
+      legacyMint,
+      bobLegacyTokenAccount.address,
+      payer,
+      legacyWantedAmount.toNumber(),
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+
+    const offerId = getRandomBigNumber();
+    const basketOffer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("basket_offer"),
+        alice.publicKey.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    const offeredAmounts = [new BN(1_000), new BN(2_000)];
+    const offeredMints = [accounts.tokenMintA, accounts.tokenMintB];
+    const vaults = offeredMints.map((mint) =>
+      getAssociatedTokenAddressSync(mint, basketOffer, true, TOKEN_PROGRAM)
+    );
+    const bobOfferedTokenAccounts = [
+      accounts.takerTokenAccountA,
+      accounts.takerTokenAccountB,
+    ];
+
+    const readOnly = (pubkey: PublicKey) => ({
+      pubkey,
+      isSigner: false,
+      isWritable: false,
+    });
+    const writable = (pubkey: PublicKey) => ({
+      pubkey,
+      isSigner: false,
+      isWritable: true,
+    });
+
+    const basketAccounts = {
+      basketOffer,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+    };
+
+    try {
+      await program.methods
+        .makeBasketOffer(offerId, offeredAmounts, [
+          { mint: legacyMint, amount: legacyWantedAmount },
+          { mint: legacyMint, amount: legacyWantedAmount },
+        ])
+        .accounts({ ...basketAccounts, maker: alice.publicKey })
+        .remainingAccounts([
+          readOnly(accounts.tokenMintA),
+          writable(accounts.makerTokenAccountA),
+          writable(vaults[0]),
+          readOnly(accounts.tokenMintB),
+          writable(accounts.makerTokenAccountB),
+          writable(vaults[1]),
+        ])
+        .signers([alice])
+        .rpc();
+      assert.fail("Wanting the same token twice should fail");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "DuplicateBasketMint");
+    }
+
+    // Someone else creating one of the vaults first must not block the offer
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      accounts.tokenMintA,
+      basketOffer,
+      true,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM
+    );
+
+    const makeBasketOfferSignature = await program.methods
+      .makeBasketOffer(offerId, offeredAmounts, [
+        { mint: legacyMint, amount: legacyWantedAmount },
+      ])
+      .accounts({ ...basketAccounts, maker: alice.publicKey })
+      .remainingAccounts([
+        readOnly(accounts.tokenMintA),
+        writable(accounts.makerTokenAccountA),
+        writable(vaults[0]),
+        readOnly(accounts.tokenMintB),
+        writable(accounts.makerTokenAccountB),
+        writable(vaults[1]),
+      ])
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeBasketOfferSignature);
+
+    const basketOfferAccount = await program.account.basketOffer.fetch(
+      basketOffer
+    );
+    assert.equal(basketOfferAccount.offered.length, 2);
+    assert(basketOfferAccount.wanted[0].mint.equals(legacyMint));
+
+    const bobBalancesBefore = await Promise.all(
+      bobOfferedTokenAccounts.map(async (tokenAccount) => {
+        const response = await connection.getTokenAccountBalance(tokenAccount);
+        return new BN(response.value.amount);
+      })
+    );
+
+    const takeBasketOfferSignature = await program.methods
+      .takeBasketOffer()
+      .accounts({
+        ...basketAccounts,
+        taker: bob.publicKey,
+        maker: alice.publicKey,
+      })
+      .remainingAccounts([
+        // What Alice wants
+        readOnly(legacyMint),
+        writable(bobLegacyTokenAccount.address),
+        writable(
+          getAssociatedTokenAddressSync(
+            legacyMint,
+            alice.publicKey,
+            false,
+            TOKEN_PROGRAM_ID
+          )
+        ),
+        // What Alice offers
+        readOnly(accounts.tokenMintA),
+        writable(vaults[0]),
+        writable(bobOfferedTokenAccounts[0]),
+        readOnly(accounts.tokenMintB),
+        writable(vaults[1]),
+        writable(bobOfferedTokenAccounts[1]),
+      ])
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, takeBasketOfferSignature);
+
+    // Bob received every offered token
+    for (const [index, tokenAccount] of bobOfferedTokenAccounts.entries()) {
+      const response = await connection.getTokenAccountBalance(tokenAccount);
+      const balanceAfter = new BN(response.value.amount);
+      assert(
+        balanceAfter.sub(bobBalancesBefore[index]).eq(offeredAmounts[index])
+      );
+    }
+
+    const aliceLegacyBalanceResponse = await connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(
+        legacyMint,
+        alice.publicKey,
+        false,
+        TOKEN_PROGRAM_ID
+      )
+    );
+    assert.equal(
+      aliceLegacyBalanceResponse.value.amount,
+      legacyWantedAmount.toString()
+    );
+
+    for (const vault of vaults) {
+      assert((await connection.getAccountInfo(vault)) === null);
+    }
+    assert((await connection.getAccountInfo(basketOffer)) === null);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Only fills a collection offer with a verified NFT from the collection", async () => {
+    const offerId = getRandomBigNumber();
+
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    accounts.offer = offer;
+    accounts.vault = getAssociatedTokenAddressSync(
+      accounts.tokenMintA,
+      offer,
+      true,
+      TOKEN_PROGRAM
+    );
+
+    // Alice bids on one NFT of the collection whose collection mint is token B
+    const makeOfferSignature = await program.methods
+      .makeOffer(offerId, tokenAOfferedAmount, new BN(1), {
+        ...defaultOfferOptions,
+        isCollectionOffer: true,
+      })
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    const offerAccount = await program.account.offer.fetch(offer);
+    assert(offerAccount.isCollectionOffer);
+
+    // Bob mints an NFT without Metaplex metadata, so it is in no collection
+    const nftMint = await createMint(
+      connection,
+      payer,
+      payer.publicKey,
+      null,
+      0,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const bobNftTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      nftMint,
+      bob.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      payer,
+      nftMint,
+      bobNftTokenAccount.address,
+      payer,
+      1,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+
+    try {
+      await program.methods
+        .takeOffer(new BN(1), tokenAOfferedAmount)
+        .accounts({
+          ...accounts,
+          tokenMintB: nftMint,
+          takerTokenAccountB: bobNftTokenAccount.address,
+          makerTokenAccountB: getAssociatedTokenAddressSync(
+            nftMint,
+            alice.publicKey,
+            false,
+            TOKEN_PROGRAM_ID
+          ),
+          // Collection offers are never charged a fee, so no fee account is needed
+          feeRecipientTokenAccountB: null,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .signers([bob])
+        .rpc();
+      assert.fail("An NFT without collection metadata should be rejected");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "MissingNftMetadata");
+    }
+
+    const refundOfferSignature = await program.methods
+      .refundOffer()
+      .accounts({ ...accounts })
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, refundOfferSignature);
+  }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
+
+  it("Returns the vault rent to Alice and repays Bob for creating Alice's token account", async () => {
+    // Alice wants a token she has no token account for yet
+    const wantedMint = await createMint(
+      connection,
+      payer,
+      payer.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const bobWantedTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      wantedMint,
+      bob.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      payer,
+      wantedMint,
+      bobWantedTokenAccount.address,
+      payer,
+      tokenBWantedAmount.muln(2).toNumber(),
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    // The fee recipient's token account for the wanted token
+    const feeRecipientWantedTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      wantedMint,
+      accounts.feeRecipient,
+      false,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+
+    const offerId = getRandomBigNumber();
+    const offer = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offer"),
+        accounts.maker.toBuffer(),
+        offerId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+    const vault = getAssociatedTokenAddressSync(
+      accounts.tokenMintA,
+      offer,
+      true,
+      TOKEN_PROGRAM
+    );
+    const aliceWantedTokenAccount = getAssociatedTokenAddressSync(
+      wantedMint,
+      alice.publicKey,
+      false,
+      TOKEN_PROGRAM_ID
+    );
+
+    const rentAccounts = {
+      ...accounts,
+      tokenMintB: wantedMint,
+      takerTokenAccountB: bobWantedTokenAccount.address,
+      makerTokenAccountB: aliceWantedTokenAccount,
+      feeRecipientTokenAccountB: feeRecipientWantedTokenAccount.address,
+      offer,
+      vault,
+      tokenProgramB: TOKEN_PROGRAM_ID,
+    };
+
+    const makeOfferSignature = await program.methods
+      .makeOffer(offerId, tokenAOfferedAmount, tokenBWantedAmount, {
+        ...defaultOfferOptions,
+        reimburseTakerRent: true,
+      })
+      .accounts(rentAccounts)
+      .signers([alice])
+      .rpc();
+
+    await confirmTransaction(connection, makeOfferSignature);
+
+    // The escrowed reimbursement is the rent of a legacy token account
+    const tokenAccountRent = await connection.getMinimumBalanceForRentExemption(
+      165
+    );
+    const offerAccount = await program.account.offer.fetch(offer);
+    assert(offerAccount.takerRentReimbursement.eqn(tokenAccountRent));
+
+    const offerLamports = await connection.getBalance(offer);
+    const vaultLamports = await connection.getBalance(vault);
+    const aliceLamportsBefore = await connection.getBalance(alice.publicKey);
+    const bobLamportsBefore = await connection.getBalance(bob.publicKey);
+
+    // The provider pays the transaction fee, so only rent moves lamports
+    const takeOfferSignature = await program.methods
+      .takeOffer(tokenBWantedAmount, tokenAOfferedAmount)
+      .accounts(rentAccounts)
+      .signers([bob])
+      .rpc();
+
+    await confirmTransaction(connection, takeOfferSignature);
+
+    const aliceLamportsAfter = await connection.getBalance(alice.publicKey);
+    const bobLamportsAfter = await connection.getBalance(bob.publicKey);
+
+    // Alice gets back the vault and offer rent, less the reimbursement
+    assert.equal(
+      aliceLamportsAfter - aliceLamportsBefore,
+      offerLamports + vaultLamports - tokenAccountRent
+    );
+    // Bob paid for Alice's token account and was repaid in full
+    assert.equal(bobLamportsAfter, bobLamportsBefore);
+
+    const aliceWantedBalanceResponse = await connection.getTokenAccountBalance(
+      aliceWantedTokenAccount
+    );
+    assert.equal(
+      aliceWantedBalanceResponse.value.amount,
+      tokenBWantedAmount.toString()
+    );
   }).slow(ANCHOR_SLOW_TEST_THRESHOLD);
 });